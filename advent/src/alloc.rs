@@ -0,0 +1,199 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNTER: Counter = Counter::new();
+
+/// allocation statistics since the last call to `reset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub peak_bytes: usize,
+    pub allocations: usize,
+}
+
+/// currently allocated bytes, the peak and the number of allocations.
+/// The peak is reported relative to the bytes that were allocated at the
+/// last reset, so memory still held from before doesn't count
+struct Counter {
+    current: AtomicUsize,
+    baseline: AtomicUsize,
+    peak: AtomicUsize,
+    count: AtomicUsize,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Counter {
+            current: AtomicUsize::new(0),
+            baseline: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    fn add(&self, size: usize) {
+        let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn sub(&self, size: usize) {
+        // memory allocated before counting was enabled might be freed,
+        // so don't go below zero
+        let _ = self
+            .current
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |c| {
+                Some(c.saturating_sub(size))
+            });
+    }
+
+    fn reset(&self) {
+        let current = self.current.load(Ordering::Relaxed);
+        self.baseline.store(current, Ordering::Relaxed);
+        self.peak.store(current, Ordering::Relaxed);
+        self.count.store(0, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> Stats {
+        let baseline = self.baseline.load(Ordering::Relaxed);
+        Stats {
+            peak_bytes: self.peak.load(Ordering::Relaxed).saturating_sub(baseline),
+            allocations: self.count.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counting wraps the system allocator and keeps track of
+/// the currently allocated bytes, the peak and the number of allocations.
+/// Nothing is counted until `enable` is called.
+pub struct Counting;
+
+impl Counting {
+    fn add(size: usize) {
+        if ENABLED.load(Ordering::Relaxed) {
+            COUNTER.add(size);
+        }
+    }
+
+    fn sub(size: usize) {
+        if ENABLED.load(Ordering::Relaxed) {
+            COUNTER.sub(size);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::sub(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // a reallocation counts as freeing the old and allocating the new block
+            Self::sub(layout.size());
+            Self::add(new_size);
+        }
+        new_ptr
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// starts a new measurement.
+/// All threads are counted, so a solver abandoned after a timeout
+/// that is still running adds to the measurement of the next part
+pub fn reset() {
+    COUNTER.reset();
+}
+
+pub fn stats() -> Stats {
+    COUNTER.stats()
+}
+
+/// formats a byte count with a binary unit
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter() {
+        let counter = Counter::new();
+        counter.add(100);
+        counter.add(50);
+        counter.sub(100);
+        assert_eq!(
+            counter.stats(),
+            Stats {
+                peak_bytes: 150,
+                allocations: 2
+            }
+        );
+
+        // the next peak is the growth above the 50 bytes still allocated
+        counter.reset();
+        counter.sub(50);
+        counter.add(30);
+        assert_eq!(
+            counter.stats(),
+            Stats {
+                peak_bytes: 0,
+                allocations: 1
+            }
+        );
+        counter.add(40);
+        assert_eq!(counter.stats().peak_bytes, 20);
+
+        // frees of memory that was never counted stop at zero
+        counter.sub(1000);
+        assert_eq!(counter.current.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_bytes(3 << 40), "3072.0 GiB");
+    }
+}
@@ -1,122 +1,546 @@
+mod alloc;
+
 use argh::FromArgs;
-use std::error;
+use budget::{Budget, Exhausted};
+use day01::{vocabulary::Vocabulary, Policy, Scanner};
+use std::{
+    error, fs, panic,
+    str::FromStr,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+#[global_allocator]
+static ALLOCATOR: alloc::Counting = alloc::Counting;
+
+/// additional time for solvers checking the budget to notice the deadline
+const TIMEOUT_GRACE: Duration = Duration::from_millis(100);
+
+const INPUTS: [&str; 15] = [
+    include_str!("../../inputs/day01.txt"),
+    include_str!("../../inputs/day02.txt"),
+    include_str!("../../inputs/day03.txt"),
+    include_str!("../../inputs/day04.txt"),
+    include_str!("../../inputs/day05.txt"),
+    include_str!("../../inputs/day06.txt"),
+    include_str!("../../inputs/day07.txt"),
+    include_str!("../../inputs/day08.txt"),
+    include_str!("../../inputs/day09.txt"),
+    include_str!("../../inputs/day10.txt"),
+    include_str!("../../inputs/day11.txt"),
+    include_str!("../../inputs/day12.txt"),
+    include_str!("../../inputs/day13.txt"),
+    include_str!("../../inputs/day14.txt"),
+    include_str!("../../inputs/day15.txt"),
+];
 
 #[derive(FromArgs, PartialEq, Debug)]
 /// day and part options
 struct Options {
     /// what day to run
     #[argh(positional)]
-    day: usize,
+    day: Option<usize>,
 
     /// optional selection for what part to calculate
     #[argh(option, short = 'p')]
     part: Option<u8>,
+
+    /// optional input file to use instead of the bundled input
+    #[argh(option, short = 'i')]
+    input: Option<String>,
+
+    /// optional time limit per part in seconds
+    #[argh(option, from_str_fn(parse_timeout))]
+    timeout: Option<Duration>,
+
+    /// report time, peak memory and allocations per part
+    #[argh(switch)]
+    mem: bool,
+
+    /// print tracing spans and their durations to stderr
+    #[argh(switch, short = 'v')]
+    trace: bool,
+
+    /// explain how the answers were reached
+    #[argh(switch)]
+    explain: bool,
+
+    /// print the explanation as JSON, if the day supports it
+    #[argh(switch)]
+    json: bool,
+
+    /// optional digit vocabulary config for day 1
+    #[argh(option)]
+    vocabulary: Option<String>,
+
+    /// list the lines of the input a day can't handle (day 1) or the
+    /// unmapped values of the almanac (day 5)
+    #[argh(switch)]
+    validate: bool,
+
+    /// how day 1 handles lines without digits: skip, zero or fail
+    #[argh(option, default = "Policy::Skip")]
+    missing_digits: Policy,
+
+    /// how day 10 counts the enclosed tiles: flood, shoelace or scanline
+    #[argh(option, default = "day10::Method::Flood")]
+    area: day10::Method,
+
+    #[argh(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum Command {
+    Gen(GenOptions),
+    Viz(VizOptions),
+    Export(ExportOptions),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// generate a synthetic input for a day
+#[argh(subcommand, name = "gen")]
+struct GenOptions {
+    /// what day to generate an input for
+    #[argh(positional)]
+    day: usize,
+
+    /// seed for the random number generator
+    #[argh(option, default = "0")]
+    seed: u64,
+
+    /// size of the generated input, its meaning depends on the day
+    #[argh(option)]
+    size: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum VizFormat {
+    Text,
+    Svg,
+}
+
+impl FromStr for VizFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(VizFormat::Text),
+            "svg" => Ok(VizFormat::Svg),
+            _ => Err(format!("unknown format \"{}\", use text or svg", s)),
+        }
+    }
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// visualise how a day processes its input
+#[argh(subcommand, name = "viz")]
+struct VizOptions {
+    /// what day to visualise
+    #[argh(positional)]
+    day: usize,
+
+    /// output format: text or svg
+    #[argh(option, default = "VizFormat::Text")]
+    format: VizFormat,
+
+    /// optional input file to use instead of the bundled input
+    #[argh(option, short = 'i')]
+    input: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum ExportFormat {
+    Dot,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(ExportFormat::Dot),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!("unknown format \"{}\", use dot or json", s)),
+        }
+    }
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// export the parsed input of a day
+#[argh(subcommand, name = "export")]
+struct ExportOptions {
+    /// what day to export
+    #[argh(positional)]
+    day: usize,
+
+    /// output format: dot or json
+    #[argh(option)]
+    format: ExportFormat,
+
+    /// optional input file to use instead of the bundled input
+    #[argh(option, short = 'i')]
+    input: Option<String>,
+}
+
+/// the input file if given, otherwise the bundled input of the day
+fn load_input(day: usize, path: Option<&str>) -> Result<String, Box<dyn error::Error>> {
+    if day < 1 || day > INPUTS.len() {
+        return Err("Invalid day selected!".into());
+    }
+
+    let input = match path {
+        Some(path) => fs::read_to_string(path)?,
+        None => INPUTS[day - 1].to_string(),
+    };
+
+    // input files usually end with a newline, the solvers expect none
+    Ok(input.trim_end_matches('\n').to_string())
+}
+
+fn visualise(opts: &VizOptions) -> Result<String, Box<dyn error::Error>> {
+    let input = load_input(opts.day, opts.input.as_deref())?;
+
+    let diagram = match opts.day {
+        5 => {
+            let almanac = day05::parse_almanac(&input)?;
+            match opts.format {
+                VizFormat::Text => day05::viz::text(&almanac),
+                VizFormat::Svg => day05::viz::svg(&almanac),
+            }
+        }
+        _ => return Err(format!("No visualisation available for day {}", opts.day).into()),
+    };
+
+    Ok(diagram)
+}
+
+fn export(opts: &ExportOptions) -> Result<String, Box<dyn error::Error>> {
+    let input = load_input(opts.day, opts.input.as_deref())?;
+
+    let export = match opts.day {
+        8 => {
+            let network = day08::Network::parse(&input)?;
+            let ghosts = day08::ghosts(&network, &Budget::unlimited())?;
+            match opts.format {
+                ExportFormat::Dot => day08::export::dot(&network, &ghosts),
+                ExportFormat::Json => day08::export::json(&network, &ghosts) + "\n",
+            }
+        }
+        _ => return Err(format!("No export available for day {}", opts.day).into()),
+    };
+
+    Ok(export)
+}
+
+fn generate(opts: &GenOptions) -> Result<String, Box<dyn error::Error>> {
+    let (seed, size) = (opts.seed, opts.size);
+
+    let input = match opts.day {
+        1 => day01::generator::generate(seed, size),
+        2 => day02::generator::generate(seed, size),
+        3 => day03::generator::generate(seed, size),
+        4 => day04::generator::generate(seed, size),
+        5 => day05::generator::generate(seed, size),
+        6 => day06::generator::generate(seed, size),
+        7 => day07::generator::generate(seed, size),
+        8 => day08::generator::generate(seed, size),
+        9 => day09::generator::generate(seed, size),
+        10 => day10::generator::generate(seed, size),
+        11 => day11::generator::generate(seed, size),
+        12 => day12::generator::generate(seed, size),
+        13 => day13::generator::generate(seed, size),
+        14 => day14::generator::generate(seed, size),
+        15 => day15::generator::generate(seed, size),
+        _ => return Err("Invalid day selected!".into()),
+    };
+
+    Ok(input)
+}
+
+/// configuration for solvers that support it
+#[derive(Debug, Clone)]
+struct Settings {
+    vocabulary: Vocabulary,
+    missing_digits: Policy,
+    area: day10::Method,
+}
+
+/// errors that stop a single part, but not the whole program
+type SolveError = Box<dyn error::Error + Send + Sync>;
+
+/// breakdown of the answers as JSON, if the day supports it
+fn explain_json(day: usize, input: &str) -> Option<Result<String, Box<dyn error::Error>>> {
+    let explanation = match day {
+        7 => day07::explain_json(input).map_err(Box::from),
+        _ => return None,
+    };
+
+    Some(explanation)
+}
+
+/// human readable breakdown of the answers for a day, if the day supports it
+fn explain(day: usize, input: &str, settings: &Settings) -> Option<String> {
+    let explanation = match day {
+        1 => day01::explain_with_vocabulary(input, &settings.vocabulary),
+        2 => day02::explain(input),
+        4 => day04::explain(input),
+        7 => day07::explain(input),
+        9 => day09::explain(input),
+        13 => day13::explain(input),
+        15 => day15::explain(input),
+        _ => return None,
+    };
+
+    Some(explanation)
+}
+
+/// lines of the input each part can't handle, if the day supports it
+fn validate(day: usize, input: &str, settings: &Settings) -> Option<String> {
+    let report = |part: u8, lines: Vec<usize>| {
+        if lines.is_empty() {
+            format!("Part {}: every line is valid\n", part)
+        } else {
+            format!("Part {}: {}\n", part, day01::MissingDigits { lines })
+        }
+    };
+
+    let validation = match day {
+        1 => {
+            report(1, day01::validate(input, &Scanner::digits()))
+                + &report(
+                    2,
+                    day01::validate(input, &Scanner::new(&settings.vocabulary)),
+                )
+        }
+        5 => match day05::validate(input) {
+            Ok(gaps) if gaps.is_empty() => "every map is contiguous\n".to_string(),
+            Ok(gaps) => gaps
+                .iter()
+                .map(|g| format!("{} (they map to themselves)\n", g))
+                .collect(),
+            Err(e) => format!("{}\n", e),
+        },
+        _ => return None,
+    };
+
+    Some(validation)
+}
+
+/// calculates a single part of a day.
+/// only the solvers that might run endlessly check the budget
+fn solve(
+    day: usize,
+    part: u8,
+    input: &str,
+    settings: &Settings,
+    budget: &Budget,
+) -> Result<String, SolveError> {
+    let result = match (day, part) {
+        (1, 1) => day01::calibrate(input, &Scanner::digits(), settings.missing_digits)?.to_string(),
+        (1, 2) => {
+            let scanner = Scanner::new(&settings.vocabulary);
+            day01::calibrate(input, &scanner, settings.missing_digits)?.to_string()
+        }
+        (2, 1) => day02::part1(input),
+        (2, 2) => day02::part2(input),
+        (3, 1) => day03::part1(input),
+        (3, 2) => day03::part2(input),
+        (4, 1) => day04::part1_checked(input)?,
+        (4, 2) => day04::part2_checked(input)?,
+        (5, 1) => day05::part1_checked(input)?,
+        (5, 2) => day05::part2_with_budget(input, budget)?,
+        (6, 1) => day06::part1(input),
+        (6, 2) => day06::part2(input),
+        (7, 1) => day07::part1_checked(input)?,
+        (7, 2) => day07::part2_checked(input)?,
+        (8, 1) => day08::part1_with_budget(input, budget)?,
+        (8, 2) => day08::part2_with_budget(input, budget)?,
+        (9, 1) => day09::part1_checked(input)?,
+        (9, 2) => day09::part2_checked(input)?,
+        (10, 1) => day10::part1(input),
+        (10, 2) => day10::part2_with_method(input, settings.area),
+        (11, 1) => day11::part1(input),
+        (11, 2) => day11::part2(input, 1000000),
+        (12, 1) => day12::part1(input),
+        (12, 2) => day12::part2(input),
+        (13, 1) => day13::part1(input),
+        (13, 2) => day13::part2(input),
+        (14, 1) => day14::part1(input),
+        (14, 2) => day14::part2_with_budget(input, budget)?,
+        (15, 1) => day15::part1(input),
+        (15, 2) => day15::part2(input),
+        _ => unreachable!(),
+    };
+
+    Ok(result)
+}
+
+/// a positive and finite amount of seconds
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds = value
+        .parse::<f64>()
+        .map_err(|_| format!("invalid timeout \"{}\"", value))?;
+
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(timeout) if !timeout.is_zero() => Ok(timeout),
+        _ => Err(format!(
+            "invalid timeout \"{}\", use a positive number of seconds",
+            value
+        )),
+    }
+}
+
+/// runs a part on its own thread.
+/// solvers checking the budget stop by themselves once the deadline is reached,
+/// all others are abandoned when the timeout is over.
+/// An abandoned solver keeps running on its thread until it finishes
+/// or the program exits, so it still uses CPU time and memory
+fn solve_with_timeout(
+    day: usize,
+    part: u8,
+    input: &str,
+    settings: &Settings,
+    timeout: Duration,
+) -> Result<String, SolveError> {
+    let budget = Budget::unlimited().with_timeout(timeout);
+    let canceller = budget.canceller();
+    let input = input.to_string();
+    let settings = settings.clone();
+
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        // the receiver is gone after a timeout, so the result can be ignored
+        let _ = sender.send(solve(day, part, &input, &settings, &budget));
+    });
+
+    match receiver.recv_timeout(timeout.saturating_add(TIMEOUT_GRACE)) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            canceller.cancel();
+            Err(Exhausted::Timeout.into())
+        }
+        Err(RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(payload) => panic::resume_unwind(payload),
+            Ok(()) => unreachable!("the solver always sends a result"),
+        },
+    }
 }
 
 fn main() -> Result<(), Box<dyn error::Error>> {
     let opts: Options = argh::from_env();
 
-    let parts = match opts.part {
-        Some(1) => (true, false),
-        Some(2) => (false, true),
-        _ => (true, true),
+    if opts.trace {
+        tracing_subscriber::fmt()
+            .with_max_level(Level::TRACE)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
+    match &opts.command {
+        Some(Command::Gen(gen_opts)) => {
+            println!("{}", generate(gen_opts)?);
+            return Ok(());
+        }
+        Some(Command::Viz(viz_opts)) => {
+            print!("{}", visualise(viz_opts)?);
+            return Ok(());
+        }
+        Some(Command::Export(export_opts)) => {
+            print!("{}", export(export_opts)?);
+            return Ok(());
+        }
+        None => (),
+    }
+
+    let Some(day) = opts.day else {
+        return Err("No day selected!".into());
     };
 
-    let inputs = [
-        include_str!("../../inputs/day01.txt"),
-        include_str!("../../inputs/day02.txt"),
-        include_str!("../../inputs/day03.txt"),
-        include_str!("../../inputs/day04.txt"),
-        include_str!("../../inputs/day05.txt"),
-        include_str!("../../inputs/day06.txt"),
-        include_str!("../../inputs/day07.txt"),
-        include_str!("../../inputs/day08.txt"),
-        include_str!("../../inputs/day09.txt"),
-        include_str!("../../inputs/day10.txt"),
-        include_str!("../../inputs/day11.txt"),
-        include_str!("../../inputs/day12.txt"),
-        include_str!("../../inputs/day13.txt"),
-        include_str!("../../inputs/day14.txt"),
-        include_str!("../../inputs/day15.txt"),
-    ];
-
-    if opts.day < 1 && opts.day > inputs.len() {
-        return Err("Invalid day selected!".into());
+    let input = load_input(day, opts.input.as_deref())?;
+    let input = input.as_str();
+    println!("Selected day: {}", &day);
+
+    let settings = Settings {
+        vocabulary: match &opts.vocabulary {
+            Some(path) => fs::read_to_string(path)?.parse()?,
+            None => Vocabulary::english(),
+        },
+        missing_digits: opts.missing_digits,
+        area: opts.area,
+    };
+
+    if opts.validate {
+        match validate(day, input, &settings) {
+            Some(validation) => print!("{}", validation),
+            None => println!("No validation available for day {}", day),
+        }
+    }
+
+    if opts.explain && opts.json {
+        match explain_json(day, input) {
+            Some(explanation) => println!("{}", explanation?),
+            None => println!("No JSON explanation available for day {}", day),
+        }
+    } else if opts.explain {
+        match explain(day, input, &settings) {
+            Some(explanation) => print!("{}", explanation),
+            None => println!("No explanation available for day {}", day),
+        }
     }
 
-    println!("Selected day: {}", &opts.day);
-    let input = *inputs.get(opts.day - 1).unwrap();
-
-    let results = match opts.day {
-        1 => (
-            parts.0.then(|| day01::part1(input)),
-            parts.1.then(|| day01::part2(input)),
-        ),
-        2 => (
-            parts.0.then(|| day02::part1(input)),
-            parts.1.then(|| day02::part2(input)),
-        ),
-        3 => (
-            parts.0.then(|| day03::part1(input)),
-            parts.1.then(|| day03::part2(input)),
-        ),
-        4 => (
-            parts.0.then(|| day04::part1(input)),
-            parts.1.then(|| day04::part2(input)),
-        ),
-        5 => (
-            parts.0.then(|| day05::part1(input)),
-            parts.1.then(|| day05::part2(input)),
-        ),
-        6 => (
-            parts.0.then(|| day06::part1(input)),
-            parts.1.then(|| day06::part2(input)),
-        ),
-        7 => (
-            parts.0.then(|| day07::part1(input)),
-            parts.1.then(|| day07::part2(input)),
-        ),
-        8 => (
-            parts.0.then(|| day08::part1(input)),
-            parts.1.then(|| day08::part2(input)),
-        ),
-        9 => (
-            parts.0.then(|| day09::part1(input)),
-            parts.1.then(|| day09::part2(input)),
-        ),
-        10 => (
-            parts.0.then(|| day10::part1(input)),
-            parts.1.then(|| day10::part2(input)),
-        ),
-        11 => (
-            parts.0.then(|| day11::part1(input)),
-            parts.1.then(|| day11::part2(input, 1000000)),
-        ),
-        12 => (
-            parts.0.then(|| day12::part1(input)),
-            parts.1.then(|| day12::part2(input)),
-        ),
-        13 => (
-            parts.0.then(|| day13::part1(input)),
-            parts.1.then(|| day13::part2(input)),
-        ),
-        14 => (
-            parts.0.then(|| day14::part1(input)),
-            parts.1.then(|| day14::part2(input)),
-        ),
-        15 => (
-            parts.0.then(|| day15::part1(input)),
-            parts.1.then(|| day15::part2(input)),
-        ),
-
-        _ => (None, None),
+    let parts: &[u8] = match opts.part {
+        Some(1) => &[1],
+        Some(2) => &[2],
+        _ => &[1, 2],
     };
 
-    if let Some(r) = results.0 {
-        println!("Part 1: {}", r)
+    if opts.mem {
+        alloc::enable();
     }
-    if let Some(r) = results.1 {
-        println!("Part 2: {}", r)
+
+    // a part that timed out might still be running and allocating
+    let mut timed_out = false;
+
+    for &part in parts {
+        alloc::reset();
+        let start = Instant::now();
+
+        let result = match opts.timeout {
+            Some(timeout) => solve_with_timeout(day, part, input, &settings, timeout),
+            None => solve(day, part, input, &settings, &Budget::unlimited()),
+        };
+
+        let elapsed = start.elapsed();
+        let stats = alloc::stats();
+
+        match &result {
+            Ok(r) => println!("Part {}: {}", part, r),
+            Err(e) => println!("Part {}: {}", part, e),
+        }
+
+        if opts.mem {
+            println!(
+                "  time: {:.2?}, peak memory: {}, allocations: {}",
+                elapsed,
+                alloc::format_bytes(stats.peak_bytes),
+                stats.allocations
+            );
+            if timed_out {
+                println!("  (may include a solver abandoned after a timeout)");
+            }
+        }
+
+        timed_out |= matches!(
+            &result,
+            Err(e) if matches!(e.downcast_ref::<Exhausted>(), Some(Exhausted::Timeout))
+        );
     }
 
     Ok(())
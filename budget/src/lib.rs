@@ -0,0 +1,139 @@
+use std::{
+    error, fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// checking the clock on every step would be quite expensive,
+/// so the deadline is only checked every so many steps
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// reason why a solver had to stop early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exhausted {
+    Steps,
+    Timeout,
+    Cancelled,
+}
+
+impl fmt::Display for Exhausted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Exhausted::Steps => write!(f, "step budget exhausted"),
+            Exhausted::Timeout => write!(f, "timeout"),
+            Exhausted::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl error::Error for Exhausted {}
+
+/// handle to cancel a running solver from another thread
+#[derive(Debug, Clone)]
+pub struct Canceller(Arc<AtomicBool>);
+
+impl Canceller {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Budget is checked cooperatively by long running solvers.
+/// Every iteration of a potentially endless loop calls `step`
+/// and stops as soon as an error is returned.
+#[derive(Debug)]
+pub struct Budget {
+    max_steps: Option<u64>,
+    deadline: Option<Instant>,
+    steps: AtomicU64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Budget {
+            max_steps: None,
+            deadline: None,
+            steps: AtomicU64::new(0),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// a timeout too large for the clock means no deadline at all
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Instant::now().checked_add(timeout);
+        self
+    }
+
+    pub fn canceller(&self) -> Canceller {
+        Canceller(self.cancelled.clone())
+    }
+
+    pub fn used_steps(&self) -> u64 {
+        self.steps.load(Ordering::Relaxed)
+    }
+
+    /// account for one step of work
+    pub fn step(&self) -> Result<(), Exhausted> {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(Exhausted::Cancelled);
+        }
+
+        if self.max_steps.is_some_and(|max| steps > max) {
+            return Err(Exhausted::Steps);
+        }
+
+        if steps.is_multiple_of(CLOCK_INTERVAL)
+            && self.deadline.is_some_and(|d| Instant::now() >= d)
+        {
+            return Err(Exhausted::Timeout);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps() {
+        let budget = Budget::unlimited().with_steps(3);
+        assert_eq!(budget.step(), Ok(()));
+        assert_eq!(budget.step(), Ok(()));
+        assert_eq!(budget.step(), Ok(()));
+        assert_eq!(budget.step(), Err(Exhausted::Steps));
+    }
+
+    #[test]
+    fn timeout() {
+        let budget = Budget::unlimited().with_timeout(Duration::ZERO);
+        let result = (0..CLOCK_INTERVAL).try_for_each(|_| budget.step());
+        assert_eq!(result, Err(Exhausted::Timeout));
+    }
+
+    #[test]
+    fn cancel() {
+        let budget = Budget::unlimited();
+        assert_eq!(budget.step(), Ok(()));
+        budget.canceller().cancel();
+        assert_eq!(budget.step(), Err(Exhausted::Cancelled));
+    }
+}
@@ -0,0 +1,45 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::WORDS;
+
+/// generates `size` calibration lines made of letters, digits and spelled out digits.
+/// every line contains at least one numeric digit, so both parts can be solved
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let lines = (0..size)
+        .map(|_| {
+            let mut line = String::new();
+            let length = rng.gen_range(5..=20);
+
+            while line.len() < length {
+                match rng.gen_range(0..10) {
+                    0..=1 => line.push(char::from_digit(rng.gen_range(1..=9), 10).unwrap()),
+                    2 => line.push_str(WORDS[rng.gen_range(0..WORDS.len())]),
+                    _ => line.push(rng.gen_range(b'a'..=b'z') as char),
+                }
+            }
+
+            // make sure there is at least one numeric digit
+            let digit = char::from_digit(rng.gen_range(1..=9), 10).unwrap();
+            let index = rng.gen_range(0..=line.len());
+            line.insert(index, digit);
+
+            line
+        })
+        .collect::<Vec<_>>();
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digit_in_every_line() {
+        let input = generate(1, 100);
+        assert_eq!(input.lines().count(), 100);
+        assert_eq!(crate::validate(&input, &crate::Scanner::digits()), vec![]);
+    }
+}
@@ -1,49 +1,237 @@
-use aho_corasick::AhoCorasick;
+pub mod generator;
+pub mod vocabulary;
 
-pub fn part1(input: &str) -> String {
-    let calibration_values = input
-        .lines()
-        .map(|l| {
-            // get the digits from every line
-            let mut digits = l.chars().filter_map(|c| c.to_digit(10));
+use aho_corasick::{AhoCorasick, Anchored, Input, MatchKind, StartKind};
+use std::{error, fmt, str::FromStr};
+use tracing::instrument;
+use vocabulary::Vocabulary;
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// first and last digit of a calibration line
+/// and the byte positions where they start
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Calibration {
+    pub first: u32,
+    pub last: u32,
+    pub positions: (usize, usize),
+}
+
+impl Calibration {
+    pub fn value(&self) -> u32 {
+        10 * self.first + self.last
+    }
+}
+
+/// what to do with lines that don't contain any digit
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Policy {
+    /// leave the line out
+    #[default]
+    Skip,
+    /// use 0 as the calibration value of the line
+    Zero,
+    /// stop with an error listing all lines without digits
+    Fail,
+}
+
+impl FromStr for Policy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Policy::Skip),
+            "zero" => Ok(Policy::Zero),
+            "fail" => Ok(Policy::Fail),
+            _ => Err(format!("unknown policy \"{}\", use skip, zero or fail", s)),
+        }
+    }
+}
+
+/// line numbers (starting at 1) of all lines without digits
+#[derive(Debug, PartialEq, Eq)]
+pub struct MissingDigits {
+    pub lines: Vec<usize>,
+}
+
+impl fmt::Display for MissingDigits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self.lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        write!(f, "no digits in line(s) {}", lines.join(", "))
+    }
+}
+
+impl error::Error for MissingDigits {}
+
+/// Scanner looks for the first and last digit of a line in a single pass from both ends.
+/// The first digit is the leftmost match, the last one is found by trying an
+/// anchored match at every position going backwards from the end.
+/// This way overlapping words (e.g. "oneight") need no special treatment.
+/// If several tokens start at the same position, the longest one wins.
+pub struct Scanner {
+    automaton: AhoCorasick,
+    values: Vec<u32>,
+}
 
-            let first = digits.next().unwrap();
-            let second = digits.next_back().unwrap_or(first);
+impl Scanner {
+    /// only numeric digits
+    pub fn digits() -> Self {
+        Self::new(&Vocabulary::digits())
+    }
 
-            10 * first + second
+    /// numeric and spelled out digits
+    pub fn digits_and_words() -> Self {
+        Self::new(&Vocabulary::english())
+    }
+
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let (patterns, values): (Vec<_>, Vec<_>) = vocabulary
+            .tokens()
+            .iter()
+            .map(|(token, value)| (token.as_str(), *value))
+            .unzip();
+
+        let automaton = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .start_kind(StartKind::Both)
+            .build(patterns)
+            .expect("the tokens should form a valid automaton");
+
+        Scanner { automaton, values }
+    }
+
+    /// longest token starting exactly at `start`
+    fn token_at(&self, line: &str, start: usize) -> Option<aho_corasick::Match> {
+        let input = Input::new(line)
+            .span(start..line.len())
+            .anchored(Anchored::Yes);
+        self.automaton.find(input)
+    }
+
+    pub fn scan(&self, line: &str) -> Option<Calibration> {
+        let first = self.automaton.find(line)?;
+
+        // the last match can't start before the first one,
+        // so at the latest the first match is found again
+        let last = (first.start()..line.len())
+            .rev()
+            .find_map(|start| self.token_at(line, start))?;
+
+        Some(Calibration {
+            first: self.values[first.pattern()],
+            last: self.values[last.pattern()],
+            positions: (first.start(), last.start()),
         })
-        .collect::<Vec<_>>();
+    }
+
+    /// all tokens found in a line with their position and digit,
+    /// overlapping tokens are included
+    pub fn tokens<'a>(&self, line: &'a str) -> Vec<(usize, &'a str, u32)> {
+        (0..line.len())
+            .filter_map(|start| self.token_at(line, start))
+            .map(|m| (m.start(), &line[m.range()], self.values[m.pattern()]))
+            .collect()
+    }
+}
+
+/// line numbers (starting at 1) of all lines without any digit
+pub fn validate(input: &str, scanner: &Scanner) -> Vec<usize> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(index, l)| scanner.scan(l).is_none().then_some(index + 1))
+        .collect()
+}
+
+/// the calibration value of every line together with its line number.
+/// lines without digits are handled according to the policy
+pub fn calibration_values(
+    input: &str,
+    scanner: &Scanner,
+    policy: Policy,
+) -> Result<Vec<(usize, u32)>, MissingDigits> {
+    let mut values = Vec::new();
+    let mut missing = Vec::new();
+
+    for (index, l) in input.lines().enumerate() {
+        match (scanner.scan(l), policy) {
+            (Some(calibration), _) => values.push((index + 1, calibration.value())),
+            (None, Policy::Skip) => (),
+            (None, Policy::Zero) => values.push((index + 1, 0)),
+            (None, Policy::Fail) => missing.push(index + 1),
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(MissingDigits { lines: missing });
+    }
+
+    Ok(values)
+}
 
-    calibration_values.iter().sum::<u32>().to_string()
+/// sum of all calibration values
+pub fn calibrate(input: &str, scanner: &Scanner, policy: Policy) -> Result<u32, MissingDigits> {
+    let values = calibration_values(input, scanner, policy)?;
+
+    Ok(values.iter().map(|(_, v)| v).sum())
+}
+
+#[instrument(skip_all)]
+pub fn part1(input: &str) -> String {
+    let sum = calibrate(input, &Scanner::digits(), Policy::Skip).expect("lines are skipped");
+
+    sum.to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
-    let s_pattern = &["two", "eight"];
-    let s_replace = &["ttwoo", "eeightt"];
-    let d_pattern = &[
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ];
-    let d_replace = &["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    part2_with_vocabulary(input, &Vocabulary::english())
+}
 
-    let ac_sanitize = AhoCorasick::new(s_pattern).unwrap();
-    let ac_digitize = AhoCorasick::new(d_pattern).unwrap();
+#[instrument(skip_all)]
+pub fn part2_with_vocabulary(input: &str, vocabulary: &Vocabulary) -> String {
+    let sum = calibrate(input, &Scanner::new(vocabulary), Policy::Skip).expect("lines are skipped");
 
-    let calibration_values = input
-        .lines()
-        .map(|l| {
-            let sanitized = ac_sanitize.replace_all(l, s_replace);
-            let digitized = ac_digitize.replace_all(&sanitized, d_replace);
-            // get the digits from every line
-            let mut digits = digitized.chars().filter_map(|c| c.to_digit(10));
+    sum.to_string()
+}
+
+/// lists the first and last digit of every line for both parts
+pub fn explain(input: &str) -> String {
+    explain_with_vocabulary(input, &Vocabulary::english())
+}
 
-            let first = digits.next().unwrap();
-            let second = digits.next_back().unwrap_or(first);
+/// like `explain`, but part 2 and the matched tokens use the given vocabulary
+pub fn explain_with_vocabulary(input: &str, vocabulary: &Vocabulary) -> String {
+    let digits = Scanner::digits();
+    let words = Scanner::new(vocabulary);
 
-            10 * first + second
-        })
-        .collect::<Vec<_>>();
+    let describe = |calibration: Option<Calibration>| match calibration {
+        Some(c) => format!("{} and {} = {}", c.first, c.last, c.value()),
+        None => "none".to_string(),
+    };
+
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, l)| {
+            let tokens = words
+                .tokens(l)
+                .iter()
+                .map(|(_, token, _)| *token)
+                .collect::<Vec<_>>();
 
-    calibration_values.iter().sum::<u32>().to_string()
+            format!(
+                "line {} \"{}\": digits {}, with words {}, tokens [{}]\n",
+                index + 1,
+                l,
+                describe(digits.scan(l)),
+                describe(words.scan(l)),
+                tokens.join(", ")
+            )
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -71,9 +259,121 @@ zoneight234
         assert_eq!(result, "142");
     }
 
+    #[test]
+    fn zero_digits() {
+        // '0' is a numeric digit as well, only the word "zero" is not
+        assert_eq!(part1("a0b5c\n1x0"), "15");
+        assert_eq!(part2("zero0nine"), "9");
+    }
+
     #[test]
     fn p2() {
         let result = part2(INPUT2);
         assert_eq!(result, "281");
     }
+
+    #[test]
+    fn overlapping_words() {
+        let scanner = Scanner::digits_and_words();
+        for (line, first, last) in [
+            ("oneight", 1, 8),
+            ("sevenine", 7, 9),
+            ("threeight", 3, 8),
+            ("nineight", 9, 8),
+            ("eightwone", 8, 1),
+            ("twone", 2, 1),
+        ] {
+            let calibration = scanner.scan(line).unwrap();
+            assert_eq!(
+                (calibration.first, calibration.last),
+                (first, last),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn positions() {
+        let scanner = Scanner::digits_and_words();
+        assert_eq!(
+            scanner.scan("zoneight234"),
+            Some(Calibration {
+                first: 1,
+                last: 4,
+                positions: (1, 10)
+            })
+        );
+        assert_eq!(
+            scanner.scan("xx7xx"),
+            Some(Calibration {
+                first: 7,
+                last: 7,
+                positions: (2, 2)
+            })
+        );
+        assert_eq!(scanner.scan("abc"), None);
+    }
+
+    #[test]
+    fn other_vocabularies() {
+        let german = Vocabulary::parse(include_str!("../vocabularies/german.txt")).unwrap();
+        let result = part2_with_vocabulary("xdreinsx\nfünf\nachtzwei7", &german);
+        assert_eq!(result, (31 + 55 + 87).to_string());
+
+        let roman = Vocabulary::parse(include_str!("../vocabularies/roman.txt")).unwrap();
+        let scanner = Scanner::new(&roman);
+        assert_eq!(
+            scanner.tokens("xviiix"),
+            vec![(1, "viii", 8), (2, "iii", 3), (3, "ii", 2), (4, "ix", 9)]
+        );
+
+        let zero = Vocabulary::parse("zero = 0").unwrap();
+        assert_eq!(part2_with_vocabulary("zero5", &zero), "5");
+    }
+
+    const INPUT3: &str = "\
+1abc2
+
+treb7uchet
+abc";
+
+    #[test]
+    fn validation() {
+        assert_eq!(validate(INPUT3, &Scanner::digits()), vec![2, 4]);
+        assert_eq!(validate(INPUT1, &Scanner::digits()), vec![]);
+        assert_eq!(validate(INPUT2, &Scanner::digits()), vec![2]);
+        assert_eq!(validate(INPUT2, &Scanner::digits_and_words()), vec![]);
+    }
+
+    #[test]
+    fn policy_skip() {
+        let result = calibration_values(INPUT3, &Scanner::digits(), Policy::Skip);
+        assert_eq!(result, Ok(vec![(1, 12), (3, 77)]));
+        assert_eq!(part1(INPUT3), "89");
+    }
+
+    #[test]
+    fn policy_zero() {
+        let result = calibration_values(INPUT3, &Scanner::digits(), Policy::Zero);
+        assert_eq!(result, Ok(vec![(1, 12), (2, 0), (3, 77), (4, 0)]));
+    }
+
+    #[test]
+    fn policy_fail() {
+        let result = calibrate(INPUT3, &Scanner::digits(), Policy::Fail);
+        assert_eq!(result, Err(MissingDigits { lines: vec![2, 4] }));
+        assert_eq!(result.unwrap_err().to_string(), "no digits in line(s) 2, 4");
+
+        let result = calibrate(INPUT1, &Scanner::digits(), Policy::Fail);
+        assert_eq!(result, Ok(142));
+    }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT2);
+        assert!(result
+            .starts_with("line 1 \"two1nine\": digits 1 and 1 = 11, with words 2 and 9 = 29, tokens [two, 1, nine]\n"));
+        assert!(result.contains("line 2 \"eightwothree\": digits none, with words 8 and 3 = 83, tokens [eight, two, three]\n"));
+    }
 }
@@ -0,0 +1,150 @@
+use std::{error, fmt, str::FromStr};
+
+use crate::WORDS;
+
+/// errors in a vocabulary config, lines are counted from 1
+#[derive(Debug, PartialEq, Eq)]
+pub enum VocabularyError {
+    Malformed { line: usize },
+    InvalidValue { line: usize, value: String },
+    Duplicate { line: usize, token: String },
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VocabularyError::Malformed { line } => {
+                write!(f, "line {}: expected `token = value`", line)
+            }
+            VocabularyError::InvalidValue { line, value } => {
+                write!(
+                    f,
+                    "line {}: \"{}\" is not a digit between 0 and 9",
+                    line, value
+                )
+            }
+            VocabularyError::Duplicate { line, token } => {
+                write!(f, "line {}: token \"{}\" is defined twice", line, token)
+            }
+        }
+    }
+}
+
+impl error::Error for VocabularyError {}
+
+/// Vocabulary is the list of tokens the calibration scanner recognizes,
+/// together with the digit every token stands for.
+/// The numeric digits 0-9 are always part of a vocabulary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    tokens: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    /// only the numeric digits
+    pub fn digits() -> Self {
+        Vocabulary {
+            tokens: (0..=9).map(|d| (d.to_string(), d)).collect(),
+        }
+    }
+
+    /// numeric digits and the english words "one" to "nine"
+    pub fn english() -> Self {
+        let mut vocabulary = Self::digits();
+        vocabulary
+            .tokens
+            .extend(WORDS.iter().zip(1..).map(|(w, d)| (w.to_string(), d)));
+        vocabulary
+    }
+
+    /// parses a config with one `token = value` pair per line.
+    /// empty lines and lines starting with '#' are ignored.
+    pub fn parse(config: &str) -> Result<Self, VocabularyError> {
+        let mut vocabulary = Self::digits();
+
+        for (index, line) in config.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (token, value) = line
+                .split_once('=')
+                .map(|(t, v)| (t.trim(), v.trim()))
+                .filter(|(t, _)| !t.is_empty())
+                .ok_or(VocabularyError::Malformed { line: line_number })?;
+
+            let value = value
+                .parse::<u32>()
+                .ok()
+                .filter(|&v| v <= 9)
+                .ok_or_else(|| VocabularyError::InvalidValue {
+                    line: line_number,
+                    value: value.to_string(),
+                })?;
+
+            if vocabulary.tokens.iter().any(|(t, _)| t == token) {
+                return Err(VocabularyError::Duplicate {
+                    line: line_number,
+                    token: token.to_string(),
+                });
+            }
+
+            vocabulary.tokens.push((token.to_string(), value));
+        }
+
+        Ok(vocabulary)
+    }
+
+    pub fn tokens(&self) -> &[(String, u32)] {
+        &self.tokens
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = VocabularyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled() {
+        for config in [
+            include_str!("../vocabularies/german.txt"),
+            include_str!("../vocabularies/french.txt"),
+            include_str!("../vocabularies/roman.txt"),
+        ] {
+            let vocabulary = Vocabulary::parse(config).unwrap();
+            assert_eq!(vocabulary.tokens().len(), 19);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Vocabulary::parse("# comment\n\nnull = 0\neins 1"),
+            Err(VocabularyError::Malformed { line: 4 })
+        );
+        assert_eq!(
+            Vocabulary::parse("zehn = 10"),
+            Err(VocabularyError::InvalidValue {
+                line: 1,
+                value: "10".to_string()
+            })
+        );
+        assert_eq!(
+            Vocabulary::parse("eins = 1\neins = 1"),
+            Err(VocabularyError::Duplicate {
+                line: 2,
+                token: "eins".to_string()
+            })
+        );
+    }
+}
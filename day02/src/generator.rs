@@ -0,0 +1,52 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+/// generates `size` games with up to 6 rounds each
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let games = (1..=size)
+        .map(|id| {
+            let rounds = (0..rng.gen_range(1..=6))
+                .map(|_| {
+                    let mut colors = COLORS.to_vec();
+                    colors.shuffle(&mut rng);
+                    colors.truncate(rng.gen_range(1..=COLORS.len()));
+
+                    colors
+                        .iter()
+                        .map(|c| format!("{} {}", rng.gen_range(1..=20), c))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect::<Vec<_>>();
+
+            format!("Game {}: {}", id, rounds.join("; "))
+        })
+        .collect::<Vec<_>>();
+
+    games.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn games() {
+        let games = crate::parse_games(&generate(1, 100));
+        assert_eq!(
+            games.iter().map(|g| g.id).collect::<Vec<_>>(),
+            (1..=100).collect::<Vec<_>>()
+        );
+        for game in games.iter() {
+            assert!((1..=6).contains(&game.rounds.len()));
+            for round in game.rounds.iter() {
+                assert!((1..=3).contains(&round.len()));
+                assert!(round.keys().all(|c| COLORS.contains(&c.as_str())));
+                assert!(round.values().all(|n| (1..=20).contains(n)));
+            }
+        }
+    }
+}
@@ -1,92 +1,281 @@
-#[derive(Debug, PartialEq)]
-struct Round {
-    pub red: usize,
-    pub green: usize,
-    pub blue: usize,
-}
+pub mod generator;
+
+use std::collections::{BTreeMap, HashMap};
+use tracing::instrument;
+
+/// the colours of the bag from part 1, also used for the power in part 2
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+const MAX_RED: usize = 12;
+const MAX_GREEN: usize = 13;
+const MAX_BLUE: usize = 14;
+
+/// amount of cubes per colour, used for rounds as well as for bags
+pub type Cubes = HashMap<String, usize>;
 
 #[derive(Debug)]
-struct Game {
-    pub rounds: Vec<Round>,
+pub struct Game {
+    pub id: usize,
+    pub rounds: Vec<Cubes>,
+}
+
+impl Game {
+    /// true if every round could have been drawn from the bag.
+    /// colours missing in the bag have a limit of 0
+    pub fn is_feasible(&self, bag: &Cubes) -> bool {
+        self.rounds
+            .iter()
+            .flatten()
+            .all(|(colour, &count)| count <= bag.get(colour).copied().unwrap_or(0))
+    }
+
+    /// the least amount of cubes of every colour needed for this game
+    pub fn minimal_bag(&self) -> Cubes {
+        let mut bag = Cubes::new();
+        for (colour, &count) in self.rounds.iter().flatten() {
+            let max = bag.entry(colour.clone()).or_default();
+            *max = (*max).max(count);
+        }
+        bag
+    }
 }
 
-fn parse_round(input: &str) -> Round {
-    let mut r = 0;
-    let mut g = 0;
-    let mut b = 0;
+/// the bag from part 1: 12 red, 13 green and 14 blue cubes
+pub fn puzzle_bag() -> Cubes {
+    COLOURS
+        .iter()
+        .zip([MAX_RED, MAX_GREEN, MAX_BLUE])
+        .map(|(colour, max)| (colour.to_string(), max))
+        .collect()
+}
+
+/// product of the red, green and blue cubes in a bag
+pub fn power(bag: &Cubes) -> usize {
+    COLOURS
+        .iter()
+        .map(|&colour| bag.get(colour).copied().unwrap_or(0))
+        .product()
+}
+
+fn parse_round(input: &str) -> Cubes {
+    let mut round = Cubes::new();
 
     input.split(", ").for_each(|cube| {
         let (count, col) = cube.split_once(' ').unwrap();
         let count = count.parse::<usize>().unwrap();
-        match col {
-            "red" => r = count,
-            "green" => g = count,
-            "blue" => b = count,
-            _ => (),
-        }
+        *round.entry(col.to_string()).or_default() += count;
     });
 
-    Round {
-        red: r,
-        green: g,
-        blue: b,
-    }
+    round
 }
 
-fn parse_games(input: &str) -> Vec<Game> {
+#[instrument(skip_all)]
+pub fn parse_games(input: &str) -> Vec<Game> {
     input
         .lines()
         .map(|lines| {
-            let (_, round_data) = lines.split_once(": ").unwrap();
+            let (game, round_data) = lines.split_once(": ").unwrap();
+            let id = game
+                .strip_prefix("Game ")
+                .and_then(|id| id.parse::<usize>().ok())
+                .expect("should start with the game id");
+
             Game {
+                id,
                 rounds: round_data.split("; ").map(parse_round).collect::<Vec<_>>(),
             }
         })
         .collect::<Vec<_>>()
 }
 
+/// sum of the ids of all games which are possible with the given bag
+pub fn part1_with_bag(input: &str, bag: &Cubes) -> String {
+    let games = parse_games(input);
+
+    let valid_ids = games
+        .iter()
+        .filter(|g| g.is_feasible(bag))
+        .map(|g| g.id)
+        .collect::<Vec<_>>();
+
+    valid_ids.iter().sum::<usize>().to_string()
+}
+
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
-    const MAX_RED: usize = 12;
-    const MAX_GREEN: usize = 13;
-    const MAX_BLUE: usize = 14;
+    part1_with_bag(input, &puzzle_bag())
+}
 
+#[instrument(skip_all)]
+pub fn part2(input: &str) -> String {
     let games = parse_games(input);
 
-    let valid_rounds = games
+    let powers = games
         .iter()
-        .enumerate()
-        .filter_map(|(index, g)| {
-            g.rounds.iter().try_fold(index + 1, |acc, round| {
-                if round.red <= MAX_RED && round.green <= MAX_GREEN && round.blue <= MAX_BLUE {
-                    return Some(acc);
-                }
-
-                None
-            })
+        .map(|g| power(&g.minimal_bag()))
+        .collect::<Vec<_>>();
+
+    powers.iter().sum::<usize>().to_string()
+}
+
+/// the smallest bag with which all of the given games are possible
+pub fn smallest_bag(games: &[Game]) -> Cubes {
+    let mut bag = Cubes::new();
+    for (colour, count) in games.iter().flat_map(|g| g.minimal_bag()) {
+        let max = bag.entry(colour).or_default();
+        *max = (*max).max(count);
+    }
+    bag
+}
+
+/// share of every colour among all drawn cubes.
+/// If every round is drawn from the same bag, the pooled shares are the
+/// maximum likelihood estimate for the composition of that bag
+pub fn likely_composition(games: &[Game]) -> BTreeMap<String, f64> {
+    let mut drawn = BTreeMap::<String, usize>::new();
+    for (colour, &count) in games.iter().flat_map(|g| &g.rounds).flatten() {
+        *drawn.entry(colour.clone()).or_default() += count;
+    }
+
+    let total = drawn.values().sum::<usize>();
+    drawn
+        .into_iter()
+        .map(|(colour, count)| (colour, count as f64 / total as f64))
+        .collect()
+}
+
+/// how many cubes of a colour were shown per round, only counting rounds with that colour
+#[derive(Debug, PartialEq)]
+pub struct Distribution {
+    pub rounds: usize,
+    pub total: usize,
+    pub min: usize,
+    pub max: usize,
+    pub median: usize,
+}
+
+impl Distribution {
+    pub fn mean(&self) -> f64 {
+        self.total as f64 / self.rounds as f64
+    }
+}
+
+/// distribution of the drawn amount for every colour
+pub fn distributions(games: &[Game]) -> BTreeMap<String, Distribution> {
+    let mut counts = BTreeMap::<String, Vec<usize>>::new();
+    for (colour, &count) in games.iter().flat_map(|g| &g.rounds).flatten() {
+        counts.entry(colour.clone()).or_default().push(count);
+    }
+
+    counts
+        .into_iter()
+        .map(|(colour, mut c)| {
+            c.sort_unstable();
+            let distribution = Distribution {
+                rounds: c.len(),
+                total: c.iter().sum(),
+                min: c[0],
+                max: c[c.len() - 1],
+                median: c[c.len() / 2],
+            };
+            (colour, distribution)
         })
+        .collect()
+}
+
+/// smallest bag, likely composition and distributions of all games
+pub fn statistics(input: &str) -> String {
+    let games = parse_games(input);
+
+    let mut report = format!(
+        "smallest bag for all games: {}\n",
+        describe(&smallest_bag(&games))
+    );
+
+    let composition = likely_composition(&games)
+        .iter()
+        .map(|(colour, share)| format!("{:.1}% {}", share * 100.0, colour))
         .collect::<Vec<_>>();
+    report += &format!("likely composition: {}\n", composition.join(", "));
+
+    for (colour, d) in distributions(&games) {
+        report += &format!(
+            "{}: {} rounds, min {}, median {}, max {}, mean {:.2}\n",
+            colour,
+            d.rounds,
+            d.min,
+            d.median,
+            d.max,
+            d.mean()
+        );
+    }
 
-    valid_rounds.iter().sum::<usize>().to_string()
+    report
 }
 
-pub fn part2(input: &str) -> String {
+/// "4 red, 2 green, 6 blue", other colours follow in alphabetical order
+fn describe(bag: &Cubes) -> String {
+    let mut others = bag
+        .keys()
+        .filter(|colour| !COLOURS.contains(&colour.as_str()))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    others.sort_unstable();
+
+    COLOURS
+        .iter()
+        .copied()
+        .chain(others)
+        .map(|colour| format!("{} {}", bag.get(colour).copied().unwrap_or(0), colour))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// lists for every game which rounds exceeded the bag of part 1
+/// and the minimal bag used for part 2, followed by the statistics
+pub fn explain(input: &str) -> String {
     let games = parse_games(input);
+    let bag = puzzle_bag();
 
-    let powers = games
+    let explanation = games
         .iter()
         .map(|g| {
-            let maxs = g.rounds.iter().fold((0, 0, 0), |acc, r| {
-                let re = if r.red >= acc.0 { r.red } else { acc.0 };
-                let gr = if r.green >= acc.1 { r.green } else { acc.1 };
-                let bl = if r.blue >= acc.2 { r.blue } else { acc.2 };
-
-                (re, gr, bl)
-            });
-            maxs.0 * maxs.1 * maxs.2
+            let exceeded = g
+                .rounds
+                .iter()
+                .enumerate()
+                .flat_map(|(ri, r)| {
+                    let mut colours = r.keys().collect::<Vec<_>>();
+                    colours.sort_unstable();
+                    colours
+                        .into_iter()
+                        .map(|col| (col, r[col], bag.get(col).copied().unwrap_or(0)))
+                        .filter(|&(_, count, max)| count > max)
+                        .map(move |(col, count, max)| {
+                            format!("{} {} > {} in round {}", count, col, max, ri + 1)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let feasibility = if exceeded.is_empty() {
+                "possible".to_string()
+            } else {
+                format!("impossible ({})", exceeded.join(", "))
+            };
+
+            let minimal = g.minimal_bag();
+
+            format!(
+                "Game {}: {}, minimal bag {}, power {}\n",
+                g.id,
+                feasibility,
+                describe(&minimal),
+                power(&minimal)
+            )
         })
-        .collect::<Vec<_>>();
+        .collect::<String>();
 
-    powers.iter().sum::<usize>().to_string()
+    explanation + &statistics(input)
 }
 
 #[cfg(test)]
@@ -111,4 +300,71 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         let result = part2(INPUT);
         assert_eq!(result, "2286");
     }
+
+    #[test]
+    fn game_ids() {
+        let input = "\
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 12: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue";
+        let result = part1(input);
+        assert_eq!(result, "17");
+    }
+
+    #[test]
+    fn other_colours() {
+        let input = "\
+Game 1: 3 yellow, 1 red; 2 purple
+Game 2: 5 yellow
+Game 3: 1 blue";
+        let bag = [("yellow", 4), ("purple", 2), ("red", 1)]
+            .into_iter()
+            .map(|(colour, max)| (colour.to_string(), max))
+            .collect::<Cubes>();
+        let result = part1_with_bag(input, &bag);
+        assert_eq!(result, "1");
+
+        let games = parse_games(input);
+        assert_eq!(games[0].minimal_bag().get("purple"), Some(&2));
+        assert_eq!(power(&games[0].minimal_bag()), 0);
+        assert!(explain(input).starts_with(
+            "Game 1: impossible (3 yellow > 0 in round 1, 2 purple > 0 in round 2), minimal bag 1 red, 0 green, 0 blue, 2 purple, 3 yellow, power 0\n"
+        ));
+    }
+
+    #[test]
+    fn bag_inference() {
+        let games = parse_games(INPUT);
+        let bag = smallest_bag(&games);
+        assert_eq!(power(&bag), 20 * 13 * 15);
+        assert!(games.iter().all(|g| g.is_feasible(&bag)));
+
+        let composition = likely_composition(&games);
+        let red = composition["red"];
+        assert!((red - 61.0 / 159.0).abs() < 1e-9);
+        assert!((composition.values().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let distributions = distributions(&games);
+        assert_eq!(
+            distributions["green"],
+            Distribution {
+                rounds: 13,
+                total: 48,
+                min: 1,
+                max: 13,
+                median: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT);
+        assert!(
+            result.starts_with("Game 1: possible, minimal bag 4 red, 2 green, 6 blue, power 48\n")
+        );
+        assert!(result.contains(
+            "Game 3: impossible (20 red > 12 in round 1), minimal bag 20 red, 13 green, 6 blue, power 1560\n"
+        ));
+    }
 }
@@ -0,0 +1,67 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+
+/// generates a `size`x`size` engine schematic with numbers of up to 3 digits
+/// and randomly scattered symbols
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let lines = (0..size)
+        .map(|_| {
+            let mut line = String::with_capacity(size);
+
+            while line.len() < size {
+                let roll = rng.gen_range(0..100);
+                let length = rng.gen_range(1..=3);
+
+                if roll < 15 {
+                    // a number that doesn't fit becomes an empty cell, not a symbol
+                    if line.len() + length > size {
+                        line.push('.');
+                        continue;
+                    }
+                    line.push(char::from_digit(rng.gen_range(1..=9), 10).unwrap());
+                    for _ in 1..length {
+                        line.push(char::from_digit(rng.gen_range(0..=9), 10).unwrap());
+                    }
+                    // numbers on the same line must be separated
+                    if line.len() < size {
+                        line.push('.');
+                    }
+                } else if roll < 22 {
+                    line.push(SYMBOLS[rng.gen_range(0..SYMBOLS.len())] as char);
+                } else {
+                    line.push('.');
+                }
+            }
+
+            line
+        })
+        .collect::<Vec<_>>();
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schematic() {
+        let input = generate(1, 100);
+        assert_eq!(input.lines().count(), 100);
+        assert!(input.lines().all(|l| l.len() == 100));
+
+        let schematic = crate::Schematic::parse(&input);
+        for number in schematic.numbers.iter() {
+            // no leading zeros and no numbers running into each other
+            assert_eq!(number.value.to_string().len(), number.col_range.len());
+            assert!(number.value < 1000);
+        }
+
+        // 7 of 100 rolls are symbols, numbers take several cells
+        let share = schematic.symbols.len() as f64 / 10000.0;
+        assert!((0.04..0.07).contains(&share), "{}", share);
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+pub mod generator;
+
+use std::ops::Range;
+use tracing::instrument;
 
 #[derive(Debug, PartialEq)]
 enum Cell {
@@ -7,44 +10,7 @@ enum Cell {
     Empty,
 }
 
-/// check for symbols in the moore neighborhood
-fn check_for_symbol(grid: &Vec<Vec<Cell>>, x: usize, y: usize) -> bool {
-    let hmax = (grid[0].len() - 1).min(x + 1);
-    let hmin = if x > 0 { x - 1 } else { 0 };
-
-    let vmax = (grid.len() - 1).min(y + 1);
-    let vmin = if y > 0 { y - 1 } else { 0 };
-
-    for row in grid[vmin..=vmax].iter() {
-        for c in row[hmin..=hmax].iter() {
-            if let Cell::Symbol(_) = c {
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
-/// check for gears in the moore neighborhood and return their coordinates
-fn check_for_gear(grid: &Vec<Vec<Cell>>, x: usize, y: usize) -> Option<(usize, usize)> {
-    let hmax = (grid[0].len() - 1).min(x + 1);
-    let hmin = if x > 0 { x - 1 } else { 0 };
-
-    let vmax = (grid.len() - 1).min(y + 1);
-    let vmin = if y > 0 { y - 1 } else { 0 };
-
-    for (ypos, row) in grid[vmin..=vmax].iter().enumerate() {
-        for (xpos, c) in row[hmin..=hmax].iter().enumerate() {
-            if let Cell::Symbol('*') = c {
-                return Some((xpos + hmin, ypos + vmin));
-            }
-        }
-    }
-
-    None
-}
-
+#[instrument(skip_all)]
 fn parse_grid(input: &str) -> Vec<Vec<Cell>> {
     input
         .lines()
@@ -60,92 +26,179 @@ fn parse_grid(input: &str) -> Vec<Vec<Cell>> {
         .collect::<Vec<_>>()
 }
 
-pub fn part1(input: &str) -> String {
-    let grid = parse_grid(input);
-
-    let mut part_numbers = Vec::new();
-    let mut valid = false;
-    let mut current_number = 0;
-
-    // find all numbers next to a symbol
-    for (y, line) in grid.iter().clone().enumerate() {
-        for (x, c) in line.iter().enumerate() {
-            if let Cell::Digit(n) = c {
-                current_number = 10 * current_number + n;
-                if !valid {
-                    valid = check_for_symbol(&grid, x, y);
-                }
-            } else {
-                if current_number > 0 && valid {
-                    part_numbers.push(current_number);
-                }
-                valid = false;
-                current_number = 0;
-            }
-        }
-        if current_number > 0 && valid {
-            part_numbers.push(current_number);
-        }
-        valid = false;
-        current_number = 0;
-    }
+/// a number in the schematic, `col_range` covers all of its digits
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub col_range: Range<usize>,
+}
 
-    part_numbers.iter().sum::<u32>().to_string()
+/// a symbol in the schematic with its position as (row, column)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Symbol {
+    pub ch: char,
+    pub pos: (usize, usize),
 }
 
-pub fn part2(input: &str) -> String {
-    let grid = parse_grid(input);
-
-    let mut part_numbers_with_gears = Vec::new();
-    let mut gear = None;
-    let mut current_number = 0;
-
-    // find all numbers next to a gear
-    for (y, line) in grid.iter().clone().enumerate() {
-        for (x, c) in line.iter().enumerate() {
-            if let Cell::Digit(n) = c {
-                current_number = 10 * current_number + n;
-                if gear.is_none() {
-                    gear = check_for_gear(&grid, x, y);
-                }
-            } else {
-                if let Some(g) = gear {
-                    if current_number > 0 {
-                        part_numbers_with_gears.push((current_number, g));
+/// all numbers and symbols of a schematic and which of them are adjacent
+#[derive(Debug)]
+pub struct Schematic {
+    pub numbers: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
+    /// indices of the adjacent numbers for every symbol
+    adjacency: Vec<Vec<usize>>,
+    /// indices of the adjacent symbols for every number,
+    /// a number can touch several symbols and all of them are kept
+    symbols_of: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    #[instrument(skip_all)]
+    pub fn parse(input: &str) -> Self {
+        let grid = parse_grid(input);
+
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (row, line) in grid.iter().enumerate() {
+            let mut current: Option<PartNumber> = None;
+
+            for (col, c) in line.iter().enumerate() {
+                match (c, current.as_mut()) {
+                    (Cell::Digit(n), Some(number)) => {
+                        number.value = 10 * number.value + n;
+                        number.col_range.end = col + 1;
+                    }
+                    (Cell::Digit(n), None) => {
+                        current = Some(PartNumber {
+                            value: *n,
+                            row,
+                            col_range: col..col + 1,
+                        })
+                    }
+                    (_, _) => {
+                        numbers.extend(current.take());
+                        if let Cell::Symbol(ch) = c {
+                            symbols.push(Symbol {
+                                ch: *ch,
+                                pos: (row, col),
+                            });
+                        }
                     }
                 }
-                gear = None;
-                current_number = 0;
             }
+
+            numbers.extend(current);
         }
-        if let Some(g) = gear {
-            if current_number > 0 {
-                part_numbers_with_gears.push((current_number, g));
+
+        // index of the number covering each cell
+        let mut owners = grid
+            .iter()
+            .map(|line| vec![None; line.len()])
+            .collect::<Vec<_>>();
+        for (index, number) in numbers.iter().enumerate() {
+            for col in number.col_range.clone() {
+                owners[number.row][col] = Some(index);
             }
         }
-        gear = None;
-        current_number = 0;
-    }
 
-    // build a hashmap of all gears with their numbers
-    let mut gear_numbers: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
-    for pn in part_numbers_with_gears {
-        let key = gear_numbers.get_mut(&pn.1);
-        if let Some(g) = key {
-            g.push(pn.0);
-        } else {
-            gear_numbers.insert(pn.1, vec![pn.0]);
+        let adjacency = symbols
+            .iter()
+            .map(|symbol| {
+                let (row, col) = symbol.pos;
+                let mut adjacent = (row.saturating_sub(1)..=row + 1)
+                    .filter_map(|r| owners.get(r))
+                    .flat_map(|line| {
+                        (col.saturating_sub(1)..=col + 1)
+                            .filter_map(|c| line.get(c).copied().flatten())
+                    })
+                    .collect::<Vec<_>>();
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                adjacent
+            })
+            .collect::<Vec<_>>();
+
+        let mut symbols_of = vec![Vec::new(); numbers.len()];
+        for (symbol, adjacent) in adjacency.iter().enumerate() {
+            for &number in adjacent {
+                symbols_of[number].push(symbol);
+            }
         }
+
+        Schematic {
+            numbers,
+            symbols,
+            adjacency,
+            symbols_of,
+        }
+    }
+
+    /// all numbers adjacent to at least one symbol
+    pub fn part_numbers(&self) -> Vec<&PartNumber> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_of)
+            .filter_map(|(number, symbols)| (!symbols.is_empty()).then_some(number))
+            .collect()
     }
 
-    // filter out all gears with not exactly 2 numbers and calculate the ratio
-    let ratios = gear_numbers
-        .values()
-        .filter(|&numbers| (numbers.len() == 2))
-        .map(|numbers| numbers[0] * numbers[1])
-        .collect::<Vec<u32>>();
+    /// all symbols adjacent to the number with the given index
+    pub fn symbols_adjacent_to(&self, number: usize) -> Vec<&Symbol> {
+        self.symbols_of[number]
+            .iter()
+            .map(|&index| &self.symbols[index])
+            .collect()
+    }
 
-    ratios.iter().sum::<u32>().to_string()
+    /// all numbers adjacent to the symbol with the given index
+    pub fn numbers_adjacent_to(&self, symbol: usize) -> Vec<&PartNumber> {
+        self.adjacency[symbol]
+            .iter()
+            .map(|&index| &self.numbers[index])
+            .collect()
+    }
+
+    /// all symbols with exactly `amount` adjacent numbers, together with these numbers
+    pub fn symbols_with(&self, amount: usize) -> Vec<(&Symbol, Vec<&PartNumber>)> {
+        (0..self.symbols.len())
+            .filter(|&index| self.adjacency[index].len() == amount)
+            .map(|index| (&self.symbols[index], self.numbers_adjacent_to(index)))
+            .collect()
+    }
+
+    /// the product of the numbers of every `ch` symbol with exactly `arity` adjacent numbers.
+    /// part 2 uses '*' and 2
+    pub fn gear_ratios(&self, ch: char, arity: usize) -> Vec<u64> {
+        self.symbols_with(arity)
+            .into_iter()
+            .filter(|(symbol, _)| symbol.ch == ch)
+            .map(|(_, numbers)| numbers.iter().map(|n| u64::from(n.value)).product())
+            .collect()
+    }
+}
+
+#[instrument(skip_all)]
+pub fn part1(input: &str) -> String {
+    let schematic = Schematic::parse(input);
+
+    let part_numbers = schematic.part_numbers();
+
+    part_numbers
+        .iter()
+        .map(|n| n.value)
+        .sum::<u32>()
+        .to_string()
+}
+
+#[instrument(skip_all)]
+pub fn part2(input: &str) -> String {
+    let schematic = Schematic::parse(input);
+
+    let ratios = schematic.gear_ratios('*', 2);
+
+    ratios.iter().sum::<u64>().to_string()
 }
 
 #[cfg(test)]
@@ -175,4 +228,81 @@ mod tests {
         let result = part2(INPUT);
         assert_eq!(result, "467835");
     }
+
+    #[test]
+    fn schematic() {
+        let schematic = Schematic::parse(INPUT);
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+        assert_eq!(
+            schematic.numbers[0],
+            PartNumber {
+                value: 467,
+                row: 0,
+                col_range: 0..3
+            }
+        );
+        assert_eq!(
+            schematic.symbols[0],
+            Symbol {
+                ch: '*',
+                pos: (1, 3)
+            }
+        );
+
+        let values =
+            |numbers: Vec<&PartNumber>| numbers.iter().map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(values(schematic.numbers_adjacent_to(0)), vec![467, 35]);
+        assert_eq!(values(schematic.numbers_adjacent_to(2)), vec![617]);
+
+        let lonely = schematic.symbols_with(1);
+        assert_eq!(
+            lonely.iter().map(|(s, _)| s.ch).collect::<Vec<_>>(),
+            vec!['#', '*', '+', '$']
+        );
+        assert_eq!(schematic.gear_ratios('*', 1), vec![617]);
+        assert_eq!(schematic.gear_ratios('#', 2), vec![]);
+    }
+
+    #[test]
+    fn shared_gears() {
+        // 5 is part of both gears
+        let input = "2*5*3";
+        let schematic = Schematic::parse(input);
+        assert_eq!(
+            schematic
+                .symbols_adjacent_to(1)
+                .iter()
+                .map(|s| s.pos)
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (0, 3)]
+        );
+        assert_eq!(part2(input), (2 * 5 + 5 * 3).to_string());
+
+        // 7 bridges the gears diagonally
+        let input = "\
+4.....
+.*....
+..77..
+....*.
+.....9";
+        assert_eq!(part2(input), (4 * 77 + 77 * 9).to_string());
+    }
+
+    #[test]
+    fn grid_edges() {
+        let input = "\
+12.....34
+*.......*
+56.....78";
+        assert_eq!(part1(input), (12 + 34 + 56 + 78).to_string());
+        assert_eq!(part2(input), (12 * 56 + 34 * 78).to_string());
+
+        let input = "\
+*12
+...
+34*";
+        assert_eq!(part1(input), "46");
+        assert_eq!(part2(input), "0");
+    }
 }
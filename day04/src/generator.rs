@@ -0,0 +1,45 @@
+use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+/// generates `size` scratchcards with 10 winning numbers and 25 numbers you have
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut numbers = |amount: usize| {
+        sample(&mut rng, 99, amount)
+            .iter()
+            .map(|n| format!("{:>2}", n + 1))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let cards = (1..=size)
+        .map(|id| {
+            let winning = numbers(10);
+            let mine = numbers(25);
+            format!("Card {:>3}: {} | {}", id, winning, mine)
+        })
+        .collect::<Vec<_>>();
+
+    cards.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn cards() {
+        let cards = crate::parse_cards(&generate(1, 100)).unwrap();
+        assert_eq!(
+            cards.iter().map(|c| c.id).collect::<Vec<_>>(),
+            (1..=100).collect::<Vec<_>>()
+        );
+        for card in cards.iter() {
+            assert_eq!(card.winning_numbers.len(), 10);
+            let mine = card.my_numbers.iter().collect::<HashSet<_>>();
+            assert_eq!(mine.len(), 25);
+            assert!(mine.iter().all(|&&n| (1..=99).contains(&n)));
+        }
+    }
+}
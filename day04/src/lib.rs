@@ -1,6 +1,15 @@
+pub mod generator;
+
+use std::{
+    collections::{HashMap, HashSet},
+    error, fmt,
+};
+use tracing::instrument;
+
 #[derive(Debug)]
-struct Card {
-    winning_numbers: Vec<u32>,
+pub struct Card {
+    pub id: usize,
+    winning_numbers: HashSet<u32>,
     my_numbers: Vec<u32>,
 }
 
@@ -11,71 +20,250 @@ fn parse_numbers(input: &str) -> Vec<u32> {
         .collect::<Vec<_>>()
 }
 
-fn parse_cards(input: &str) -> Vec<Card> {
-    input
+/// a card id that was already used by another line, lines start at 1
+#[derive(Debug, PartialEq, Eq)]
+pub struct DuplicateCard {
+    pub id: usize,
+    pub line: usize,
+    pub other: usize,
+}
+
+impl fmt::Display for DuplicateCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: card {} was already defined in line {}",
+            self.line, self.id, self.other
+        )
+    }
+}
+
+impl error::Error for DuplicateCard {}
+
+#[instrument(skip_all)]
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, DuplicateCard> {
+    let cards = input
         .lines()
         .map(|line| {
-            let (_, numbers) = line.split_once(':').unwrap();
+            let (card, numbers) = line.split_once(':').unwrap();
+            let id = card
+                .strip_prefix("Card")
+                .and_then(|id| id.trim().parse::<usize>().ok())
+                .expect("should start with the card id");
             let (left, right) = numbers.split_once('|').unwrap();
             let wn = parse_numbers(left.trim());
             let mn = parse_numbers(right.trim());
             Card {
-                winning_numbers: wn,
+                id,
+                winning_numbers: wn.into_iter().collect(),
                 my_numbers: mn,
             }
         })
-        .collect::<Vec<_>>()
+        .collect::<Vec<_>>();
+
+    let mut lines = HashMap::new();
+    for (index, card) in cards.iter().enumerate() {
+        if let Some(other) = lines.insert(card.id, index + 1) {
+            return Err(DuplicateCard {
+                id: card.id,
+                line: index + 1,
+                other,
+            });
+        }
+    }
+
+    Ok(cards)
 }
 
-pub fn part1(input: &str) -> String {
-    let cards = parse_cards(input);
+impl Card {
+    /// all of my numbers which are also winning numbers
+    fn matches(&self) -> impl Iterator<Item = &u32> {
+        self.my_numbers
+            .iter()
+            .filter(|&n| self.winning_numbers.contains(n))
+    }
 
-    let points = cards
-        .iter()
-        .map(|c| {
-            let amount = c
-                .my_numbers
-                .iter()
-                .filter(|&n| c.winning_numbers.contains(n))
-                .count();
-
-            if amount == 0 {
-                return 0;
-            }
+    fn points(&self) -> u32 {
+        let amount = self.matches().count();
+
+        if amount == 0 {
+            return 0;
+        }
+
+        1 << (amount - 1)
+    }
+}
+
+/// the pile of cards grew beyond what a u128 can count
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overflow {
+    pub card: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "too many copies of card {}", self.card)
+    }
+}
+
+impl error::Error for Overflow {}
+
+/// reasons for part 2 to stop without an answer
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Duplicate(DuplicateCard),
+    Overflow(Overflow),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Duplicate(e) => e.fmt(f),
+            Error::Overflow(e) => e.fmt(f),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<DuplicateCard> for Error {
+    fn from(value: DuplicateCard) -> Self {
+        Error::Duplicate(value)
+    }
+}
+
+impl From<Overflow> for Error {
+    fn from(value: Overflow) -> Self {
+        Error::Overflow(value)
+    }
+}
+
+/// how many copies of a card there are in the end and which cards they were won from
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub id: usize,
+    /// all copies including the original card
+    pub copies: u128,
+    /// id of the source card and the amount of copies won from it
+    pub sources: Vec<(usize, u128)>,
+}
+
+#[derive(Debug)]
+pub struct Ledger {
+    pub entries: Vec<Entry>,
+}
 
-            1 << (amount - 1)
+impl Ledger {
+    /// amount of all cards in the pile
+    pub fn total(&self) -> Result<u128, Overflow> {
+        self.entries.iter().try_fold(0u128, |acc, e| {
+            acc.checked_add(e.copies).ok_or(Overflow { card: e.id })
+        })
+    }
+}
+
+/// plays all cards in the order of their ids, which are unique.
+/// every copy of a card with n matches wins a copy of each of the next n cards,
+/// cards beyond the last one are not won
+#[instrument(skip_all)]
+pub fn simulate(cards: &[Card]) -> Result<Ledger, Overflow> {
+    let mut order = (0..cards.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| cards[index].id);
+
+    let mut entries = order
+        .iter()
+        .map(|&index| Entry {
+            id: cards[index].id,
+            copies: 1,
+            sources: Vec::new(),
         })
         .collect::<Vec<_>>();
+    let positions = entries
+        .iter()
+        .enumerate()
+        .map(|(position, e)| (e.id, position))
+        .collect::<HashMap<_, _>>();
+
+    for (position, &index) in order.iter().enumerate() {
+        let card = &cards[index];
+        let multiplier = entries[position].copies;
+
+        for won in card.id + 1..=card.id + card.matches().count() {
+            let Some(&target) = positions.get(&won) else {
+                continue;
+            };
+            let entry = &mut entries[target];
+            entry.copies = entry
+                .copies
+                .checked_add(multiplier)
+                .ok_or(Overflow { card: won })?;
+            entry.sources.push((card.id, multiplier));
+        }
+    }
 
-    points.iter().sum::<u32>().to_string()
+    Ok(Ledger { entries })
 }
 
+#[instrument(skip_all)]
+pub fn part1(input: &str) -> String {
+    part1_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// like `part1`, but reports duplicate cards instead of panicking
+#[instrument(skip_all)]
+pub fn part1_checked(input: &str) -> Result<String, DuplicateCard> {
+    let cards = parse_cards(input)?;
+
+    let points = cards.iter().map(|c| c.points()).collect::<Vec<_>>();
+
+    Ok(points.iter().sum::<u32>().to_string())
+}
+
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
-    let cards = parse_cards(input);
+    part2_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
 
-    let mut card_amount = vec![1; cards.len()];
+/// like `part2`, but reports duplicate cards or a pile that can't be counted
+#[instrument(skip_all)]
+pub fn part2_checked(input: &str) -> Result<String, Error> {
+    let cards = parse_cards(input)?;
 
-    // build an iterator for the amount of winning points per card
-    let wp_count = cards.iter().map(|c| {
-        c.my_numbers
-            .iter()
-            .filter(|&n| c.winning_numbers.contains(n))
-            .count()
-    });
-
-    // go over all cards and their winning points
-    for (index, amount) in wp_count.enumerate() {
-        // get the multiplier for the current card
-        let multiplier = *card_amount.get(index).unwrap();
-
-        // increase the amount of the following cards
-        let upper = card_amount.len().min(index + 1 + amount);
-        for cm in card_amount[index + 1..upper].iter_mut() {
-            *cm += multiplier;
-        }
-    }
+    let ledger = simulate(&cards)?;
 
-    card_amount.iter().sum::<usize>().to_string()
+    Ok(ledger.total()?.to_string())
+}
+
+/// lists the matching numbers, points and copies of every card
+pub fn explain(input: &str) -> String {
+    let cards = match parse_cards(input) {
+        Ok(cards) => cards,
+        Err(e) => return format!("{}\n", e),
+    };
+    let ledger = match simulate(&cards) {
+        Ok(ledger) => ledger,
+        Err(e) => return format!("{}\n", e),
+    };
+    let copies = ledger
+        .entries
+        .iter()
+        .map(|e| (e.id, e.copies))
+        .collect::<HashMap<_, _>>();
+
+    cards
+        .iter()
+        .map(|c| {
+            let matches = c.matches().map(|n| n.to_string()).collect::<Vec<_>>();
+            format!(
+                "Card {}: {} matches [{}], {} points, {} copies\n",
+                c.id,
+                matches.len(),
+                matches.join(", "),
+                c.points(),
+                copies[&c.id]
+            )
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -101,4 +289,81 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
         let result = part2(INPUT);
         assert_eq!(result, "30");
     }
+
+    #[test]
+    fn ledger() {
+        let cards = parse_cards(INPUT).unwrap();
+        let ledger = simulate(&cards).unwrap();
+        assert_eq!(
+            ledger.entries[3],
+            Entry {
+                id: 4,
+                copies: 8,
+                sources: vec![(1, 1), (2, 2), (3, 4)]
+            }
+        );
+        assert_eq!(ledger.total(), Ok(30));
+    }
+
+    #[test]
+    fn card_ids() {
+        // the same cards, but shuffled and with a gap after card 6
+        let input = "\
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 10: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 6: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19";
+        let cards = parse_cards(input).unwrap();
+        assert_eq!(
+            cards.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![3, 10, 1, 6, 2]
+        );
+        // cards 4 and 5 are missing, so their copies are lost
+        assert_eq!(part2(input), (1 + 2 + 4 + 1 + 1).to_string());
+    }
+
+    #[test]
+    fn overflow() {
+        // every card wins a copy of all following cards, so card n has 2^(n-1) copies
+        let amount = 130;
+        let input = (1..=amount)
+            .map(|id| {
+                let numbers = (1..=amount - id)
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!("Card {}: {} | {}", id, numbers, numbers)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(
+            part2_checked(&input),
+            Err(Error::Overflow(Overflow { card: 129 }))
+        );
+    }
+
+    #[test]
+    fn duplicates() {
+        let input = INPUT.replace("Card 5:", "Card 2:");
+        let error = DuplicateCard {
+            id: 2,
+            line: 5,
+            other: 2,
+        };
+        assert_eq!(part1_checked(&input), Err(error));
+        assert!(matches!(part2_checked(&input), Err(Error::Duplicate(_))));
+        assert_eq!(
+            explain(&input),
+            "line 5: card 2 was already defined in line 2\n"
+        );
+    }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT);
+        assert!(result.starts_with("Card 1: 4 matches [83, 86, 17, 48], 8 points, 1 copies\n"));
+        assert!(result.contains("Card 5: 0 matches [], 0 points, 14 copies\n"));
+    }
 }
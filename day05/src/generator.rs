@@ -0,0 +1,90 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// generates an almanac with 10 seed numbers and `size` ranges per map.
+/// every map is a permutation of chunks of `0..size * 1000`,
+/// so the reverse lookup of part 2 always finds a seed
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let scale = size.max(1) * 1000;
+
+    let seeds = (0..5)
+        .flat_map(|_| {
+            let start = rng.gen_range(0..scale);
+            let length = rng.gen_range(1..=(scale - start).min(scale / 10).max(1));
+            [start, length]
+        })
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+
+    let mut sections = vec![format!("seeds: {}", seeds.join(" "))];
+
+    for name in crate::MAPS {
+        // split the whole value range into consecutive chunks
+        let mut cuts = (0..size.max(1) - 1)
+            .map(|_| rng.gen_range(1..scale))
+            .collect::<Vec<_>>();
+        cuts.push(0);
+        cuts.push(scale);
+        cuts.sort();
+        cuts.dedup();
+
+        let sources = cuts
+            .windows(2)
+            .map(|w| (w[0], w[1] - w[0]))
+            .collect::<Vec<_>>();
+
+        // shuffle the chunks to get the targets
+        let mut targets = sources.clone();
+        targets.shuffle(&mut rng);
+        let mut target = 0;
+        let mut ranges = targets
+            .iter()
+            .map(|&(start, length)| {
+                let range = (target, start, length);
+                target += length;
+                range
+            })
+            .collect::<Vec<_>>();
+        ranges.shuffle(&mut rng);
+
+        let lines = ranges
+            .iter()
+            .map(|(t, s, l)| format!("{} {} {}", t, s, l))
+            .collect::<Vec<_>>();
+
+        sections.push(format!("{} map:\n{}", name, lines.join("\n")));
+    }
+
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutations() {
+        let size = 20;
+        let almanac = crate::parse_almanac(&generate(1, size)).unwrap();
+        assert_eq!(almanac.seeds.len(), 10);
+
+        // the sources and targets of every map cover all values without gaps
+        let end = size as u64 * 1000;
+        for map in almanac.maps.iter() {
+            assert!(map.gaps().is_empty(), "{}", map.name);
+            assert_eq!(map.mappings[0].source, 0);
+            assert_eq!(map.mappings.last().unwrap().source_end(), Some(end));
+
+            let mut targets = map
+                .mappings
+                .iter()
+                .map(|m| (m.target, m.length))
+                .collect::<Vec<_>>();
+            targets.sort();
+            let covered = targets.iter().try_fold(0, |next, &(target, length)| {
+                (target == next).then_some(target + length)
+            });
+            assert_eq!(covered, Some(end), "{}", map.name);
+        }
+    }
+}
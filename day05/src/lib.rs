@@ -1,16 +1,179 @@
-use std::{collections::HashMap, ops::Range};
+pub mod generator;
+pub mod viz;
+
+use budget::{Budget, Exhausted};
+use std::{error, fmt, ops::Range};
+use tracing::{debug, instrument};
+
+/// all maps of an almanac in the order they are applied
+const MAPS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+/// problems found while parsing an almanac, lines start at 1
+#[derive(Debug, PartialEq, Eq)]
+pub enum AlmanacError {
+    MissingSeeds,
+    /// the seeds line contains no numbers
+    NoSeeds,
+    Malformed {
+        line: usize,
+    },
+    InvalidNumber {
+        line: usize,
+        value: String,
+    },
+    /// start + length or target + length does not fit into a u64
+    Overflow {
+        line: usize,
+    },
+    /// the source range overlaps with the one in another line of the same map
+    Overlap {
+        line: usize,
+        other: usize,
+    },
+    UnknownMap {
+        line: usize,
+        name: String,
+    },
+    /// the map was already defined in another line
+    DuplicateMap {
+        line: usize,
+        name: String,
+        other: usize,
+    },
+    MissingMap {
+        name: &'static str,
+    },
+    UnpairedSeeds,
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlmanacError::MissingSeeds => write!(f, "the almanac should start with the seeds"),
+            AlmanacError::NoSeeds => write!(f, "there are no seeds"),
+            AlmanacError::Malformed { line } => write!(f, "line {}: malformed", line),
+            AlmanacError::InvalidNumber { line, value } => {
+                write!(f, "line {}: invalid number \"{}\"", line, value)
+            }
+            AlmanacError::Overflow { line } => {
+                write!(f, "line {}: range exceeds the u64 values", line)
+            }
+            AlmanacError::Overlap { line, other } => {
+                write!(
+                    f,
+                    "line {}: source range overlaps with line {}",
+                    line, other
+                )
+            }
+            AlmanacError::UnknownMap { line, name } => {
+                write!(f, "line {}: unknown map \"{}\"", line, name)
+            }
+            AlmanacError::DuplicateMap { line, name, other } => {
+                write!(
+                    f,
+                    "line {}: map \"{}\" was already defined in line {}",
+                    line, name, other
+                )
+            }
+            AlmanacError::MissingMap { name } => write!(f, "map \"{}\" is missing", name),
+            AlmanacError::UnpairedSeeds => write!(f, "seed ranges need a start and a length"),
+        }
+    }
+}
+
+impl error::Error for AlmanacError {}
+
+/// reasons for part 2 to stop without an answer
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Almanac(AlmanacError),
+    Budget(Exhausted),
+    /// all seed ranges are empty or none of their seeds is reached from a location
+    NoLocation,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Almanac(e) => e.fmt(f),
+            Error::Budget(e) => e.fmt(f),
+            Error::NoLocation => write!(f, "no seed maps to any location"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<AlmanacError> for Error {
+    fn from(value: AlmanacError) -> Self {
+        Error::Almanac(value)
+    }
+}
+
+impl From<Exhausted> for Error {
+    fn from(value: Exhausted) -> Self {
+        Error::Budget(value)
+    }
+}
+
+/// a single line of a map, `length` values starting at `source` map to
+/// the values starting at `target`. Parsing makes sure that the last value
+/// of both sides fits into a u64, so the offsets can't overflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub target: u64,
+    pub source: u64,
+    pub length: u64,
+    /// line in the almanac
+    pub line: usize,
+}
+
+impl Mapping {
+    fn contains(start: u64, length: u64, value: u64) -> bool {
+        value >= start && value - start < length
+    }
+
+    /// the value after the last source value, `None` if that is beyond u64
+    pub fn source_end(&self) -> Option<u64> {
+        self.source.checked_add(self.length)
+    }
+}
+
+/// part of a range of `length` values and where it ends up after a map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub source: u64,
+    pub target: u64,
+    pub length: u64,
+}
+
+impl Segment {
+    pub fn offset(&self) -> i128 {
+        i128::from(self.target) - i128::from(self.source)
+    }
+}
 
 #[derive(Debug)]
-struct RangeMap {
-    ranges: Vec<(usize, Range<usize>)>,
+pub struct RangeMap {
+    pub name: String,
+    /// sorted by their source
+    pub mappings: Vec<Mapping>,
 }
 
 impl RangeMap {
     // map an index to a target
-    fn get_target(&self, index: usize) -> usize {
-        for (target, range) in self.ranges.iter() {
-            if range.contains(&index) {
-                return *target + (index - range.start);
+    pub fn get_target(&self, index: u64) -> u64 {
+        for m in self.mappings.iter() {
+            if Mapping::contains(m.source, m.length, index) {
+                return m.target + (index - m.source);
             }
         }
 
@@ -18,115 +181,315 @@ impl RangeMap {
     }
 
     // "reverse mapping" get the index from the target
-    fn get_index(&self, target: usize) -> usize {
-        for (t, range) in self.ranges.iter() {
-            let reversed = *t..*t + range.len();
-            if reversed.contains(&target) {
-                return range.start + (target - *t);
+    pub fn get_index(&self, target: u64) -> u64 {
+        for m in self.mappings.iter() {
+            if Mapping::contains(m.target, m.length, target) {
+                return m.source + (target - m.target);
             }
         }
 
         target
     }
+
+    /// splits the range of `length` values starting at `start` into the parts
+    /// covered by the same mapping (or no mapping at all) and maps them
+    pub fn map_range(&self, start: u64, length: u64) -> Vec<Segment> {
+        // the ends are calculated as u128, a range may end right after u64::MAX
+        let end = u128::from(start) + u128::from(length);
+        let mut position = u128::from(start);
+        let mut segments = Vec::new();
+
+        let mut push = |source: u128, target: u128, length: u128| {
+            if length > 0 {
+                segments.push(Segment {
+                    source: source as u64,
+                    target: target as u64,
+                    length: length as u64,
+                });
+            }
+        };
+
+        for m in self.mappings.iter() {
+            let (m_start, m_end) = (
+                u128::from(m.source),
+                u128::from(m.source) + u128::from(m.length),
+            );
+            if m_end <= position {
+                continue;
+            }
+            if m_start >= end {
+                break;
+            }
+
+            // values before the mapping stay the same
+            if m_start > position {
+                push(position, position, m_start - position);
+                position = m_start;
+            }
+
+            let segment_end = end.min(m_end);
+            push(
+                position,
+                u128::from(m.target) + (position - m_start),
+                segment_end - position,
+            );
+            position = segment_end;
+        }
+
+        push(position, position, end - position);
+
+        segments
+    }
+
+    /// source values between the first and the last range which are not mapped.
+    /// Gaps are allowed, like all values outside of the ranges they map to themselves
+    pub fn gaps(&self) -> Vec<Range<u64>> {
+        self.mappings
+            .windows(2)
+            .filter_map(|w| {
+                let end = w[0].source_end()?;
+                (end < w[1].source).then_some(end..w[1].source)
+            })
+            .collect()
+    }
 }
 
-fn parse_ranges(input: &str) -> RangeMap {
-    let mut ranges = Vec::new();
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    /// all maps in the order they are applied
+    pub maps: Vec<RangeMap>,
+}
 
-    for line in input.lines() {
-        let (target, range) = line.split_once(' ').unwrap();
-        let (start, length) = range.split_once(' ').unwrap();
+impl Almanac {
+    pub fn location(&self, seed: u64) -> u64 {
+        self.maps.iter().fold(seed, |value, m| m.get_target(value))
+    }
 
-        let target = target.parse::<usize>().unwrap();
-        let start = start.parse::<usize>().unwrap();
-        let length = length.parse::<usize>().unwrap();
+    pub fn seed(&self, location: u64) -> u64 {
+        self.maps
+            .iter()
+            .rev()
+            .fold(location, |value, m| m.get_index(value))
+    }
 
-        ranges.push((target, start..start + length));
+    /// the seed numbers as ranges of (start, length)
+    pub fn seed_ranges(&self) -> Result<Vec<(u64, u64)>, AlmanacError> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(AlmanacError::UnpairedSeeds);
+        }
+
+        Ok(self.seeds.chunks(2).map(|c| (c[0], c[1])).collect())
     }
+}
 
-    RangeMap { ranges }
+fn parse_number(value: &str, line: usize) -> Result<u64, AlmanacError> {
+    value
+        .parse::<u64>()
+        .map_err(|_| AlmanacError::InvalidNumber {
+            line,
+            value: value.to_string(),
+        })
 }
 
-fn parse_seeds_and_maps(input: &str) -> (Vec<usize>, HashMap<String, RangeMap>) {
-    let mut maps = HashMap::new();
-    let mut seeds = Vec::new();
+fn parse_mapping(input: &str, line: usize) -> Result<Mapping, AlmanacError> {
+    let numbers = input
+        .split_whitespace()
+        .map(|n| parse_number(n, line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let [target, source, length] = numbers[..] else {
+        return Err(AlmanacError::Malformed { line });
+    };
+
+    // the last value of both ranges has to fit into a u64
+    let last = length.saturating_sub(1);
+    if source.checked_add(last).is_none() || target.checked_add(last).is_none() {
+        return Err(AlmanacError::Overflow { line });
+    }
+
+    Ok(Mapping {
+        target,
+        source,
+        length,
+        line,
+    })
+}
 
-    // split the sections at empty lines
-    input.split("\n\n").for_each(|section| {
-        if section.starts_with("seeds:") {
-            seeds = section
-                .strip_prefix("seeds: ")
-                .unwrap()
-                .split_whitespace()
-                .map(|n| n.parse::<usize>().unwrap())
-                .collect();
-        } else {
-            let (key, ranges) = section.split_once(" map:\n").unwrap();
-            maps.insert(key.to_string(), parse_ranges(ranges));
+/// sorts the mappings and rejects overlapping source ranges
+fn build_map(name: String, mut mappings: Vec<Mapping>) -> Result<RangeMap, AlmanacError> {
+    mappings.retain(|m| m.length > 0);
+    mappings.sort_by_key(|m| m.source);
+
+    for w in mappings.windows(2) {
+        if w[0].source_end().is_none_or(|end| end > w[1].source) {
+            return Err(AlmanacError::Overlap {
+                line: w[1].line,
+                other: w[0].line,
+            });
+        }
+    }
+
+    let map = RangeMap { name, mappings };
+    for gap in map.gaps() {
+        debug!(map = map.name, ?gap, "unmapped values");
+    }
+
+    Ok(map)
+}
+
+#[instrument(skip_all)]
+pub fn parse_almanac(input: &str) -> Result<Almanac, AlmanacError> {
+    let mut lines = input.lines().enumerate().map(|(index, l)| (index + 1, l));
+
+    let (line, seeds) = lines.next().ok_or(AlmanacError::MissingSeeds)?;
+    let seeds = seeds
+        .strip_prefix("seeds:")
+        .ok_or(AlmanacError::MissingSeeds)?
+        .split_whitespace()
+        .map(|n| parse_number(n, line))
+        .collect::<Result<Vec<_>, _>>()?;
+    if seeds.is_empty() {
+        return Err(AlmanacError::NoSeeds);
+    }
+
+    let mut maps = Vec::new();
+    let mut current: Option<(String, Vec<Mapping>)> = None;
+    // name and line of every map header
+    let mut headers: Vec<(&str, usize)> = Vec::new();
+
+    for (line, l) in lines {
+        if l.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = l.strip_suffix(" map:") {
+            if !MAPS.contains(&name) {
+                return Err(AlmanacError::UnknownMap {
+                    line,
+                    name: name.to_string(),
+                });
+            }
+            if let Some(&(_, other)) = headers.iter().find(|(n, _)| *n == name) {
+                return Err(AlmanacError::DuplicateMap {
+                    line,
+                    name: name.to_string(),
+                    other,
+                });
+            }
+            headers.push((name, line));
+            if let Some((name, mappings)) = current.replace((name.to_string(), Vec::new())) {
+                maps.push(build_map(name, mappings)?);
+            }
+            continue;
         }
-    });
 
-    (seeds, maps)
+        match current.as_mut() {
+            Some((_, mappings)) => mappings.push(parse_mapping(l, line)?),
+            None => return Err(AlmanacError::Malformed { line }),
+        }
+    }
+
+    if let Some((name, mappings)) = current {
+        maps.push(build_map(name, mappings)?);
+    }
+
+    // bring the maps into the order in which they are applied
+    let maps = MAPS
+        .iter()
+        .map(|&name| {
+            let index = maps
+                .iter()
+                .position(|m: &RangeMap| m.name == name)
+                .ok_or(AlmanacError::MissingMap { name })?;
+            Ok(maps.swap_remove(index))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Almanac { seeds, maps })
+}
+
+/// values between the ranges of a map which map to themselves
+#[derive(Debug, PartialEq, Eq)]
+pub struct Gap {
+    pub map: String,
+    pub values: Range<u64>,
 }
 
+impl fmt::Display for Gap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: values {}..{} are not mapped",
+            self.map, self.values.start, self.values.end
+        )
+    }
+}
+
+/// the gaps of all maps in the order they are applied
+pub fn validate(input: &str) -> Result<Vec<Gap>, AlmanacError> {
+    let almanac = parse_almanac(input)?;
+
+    Ok(almanac
+        .maps
+        .iter()
+        .flat_map(|m| {
+            m.gaps().into_iter().map(|values| Gap {
+                map: m.name.clone(),
+                values,
+            })
+        })
+        .collect())
+}
+
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
-    let (seeds, maps) = parse_seeds_and_maps(input);
+    part1_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// like `part1`, but reports a malformed almanac instead of panicking
+#[instrument(skip_all)]
+pub fn part1_checked(input: &str) -> Result<String, AlmanacError> {
+    let almanac = parse_almanac(input)?;
 
     // map all seeds to their locations
-    let locations = seeds.iter().map(|seed| {
-        let soil = maps.get("seed-to-soil").unwrap().get_target(*seed);
-        let fert = maps.get("soil-to-fertilizer").unwrap().get_target(soil);
-        let water = maps.get("fertilizer-to-water").unwrap().get_target(fert);
-        let light = maps.get("water-to-light").unwrap().get_target(water);
-        let temp = maps.get("light-to-temperature").unwrap().get_target(light);
-        let humid = maps
-            .get("temperature-to-humidity")
-            .unwrap()
-            .get_target(temp);
+    let locations = almanac.seeds.iter().map(|&seed| almanac.location(seed));
 
-        maps.get("humidity-to-location").unwrap().get_target(humid)
-    });
+    let location = locations.min().ok_or(AlmanacError::NoSeeds)?;
 
-    locations.min().unwrap().to_string()
+    Ok(location.to_string())
 }
 
 pub fn part2(input: &str) -> String {
-    let (seeds, maps) = parse_seeds_and_maps(input);
-
-    // convert the seed numbers to a list of ranges
-    let seed_ranges = seeds
-        .as_slice()
-        .chunks(2)
-        .map(|c| c[0]..c[0] + c[1])
-        .collect::<Vec<_>>();
-
-    // construct an iterator starting form 0 which uses "reverse mapping"
-    // to find the required seed for that location
-    let possible_seeds = (0..).map(|location| {
-        let humid = maps
-            .get("humidity-to-location")
-            .unwrap()
-            .get_index(location);
-        let temp = maps
-            .get("temperature-to-humidity")
-            .unwrap()
-            .get_index(humid);
-        let light = maps.get("light-to-temperature").unwrap().get_index(temp);
-        let water = maps.get("water-to-light").unwrap().get_index(light);
-        let fert = maps.get("fertilizer-to-water").unwrap().get_index(water);
-        let soil = maps.get("soil-to-fertilizer").unwrap().get_index(fert);
-
-        maps.get("seed-to-soil").unwrap().get_index(soil)
-    });
-
-    // look for the first item inside the iterator that is inside
-    // one of the seed ranges
-    let (minimal_location, _) = possible_seeds
-        .enumerate()
-        .find(|&(_, seed)| seed_ranges.iter().any(|r| r.contains(&seed)))
-        .unwrap();
-
-    minimal_location.to_string()
+    part2_with_budget(input, &Budget::unlimited()).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// part 2 with a budget check for every tried location,
+/// because the search does not terminate if no seed can be reached
+#[instrument(skip_all)]
+pub fn part2_with_budget(input: &str, budget: &Budget) -> Result<String, Error> {
+    let almanac = parse_almanac(input)?;
+
+    let seed_ranges = almanac.seed_ranges()?;
+    if seed_ranges.iter().all(|&(_, length)| length == 0) {
+        return Err(Error::NoLocation);
+    }
+
+    // go through all locations starting from 0 and use "reverse mapping"
+    // to find the first one whose seed is inside one of the seed ranges
+    for location in 0..=u64::MAX {
+        budget.step()?;
+        let seed = almanac.seed(location);
+        if seed_ranges
+            .iter()
+            .any(|&(start, length)| Mapping::contains(start, length, seed))
+        {
+            debug!(location, seed, "seed found");
+            return Ok(location.to_string());
+        }
+    }
+
+    Err(Error::NoLocation)
 }
 
 #[cfg(test)]
@@ -179,4 +542,146 @@ humidity-to-location map:
         let result = part2(INPUT);
         assert_eq!(result, "46");
     }
+
+    #[test]
+    fn p2_budget() {
+        let result = part2_with_budget(INPUT, &Budget::unlimited().with_steps(46));
+        assert_eq!(result, Err(Error::Budget(Exhausted::Steps)));
+
+        let result = part2_with_budget(INPUT, &Budget::unlimited().with_steps(47));
+        assert_eq!(result, Ok("46".to_string()));
+    }
+
+    #[test]
+    fn large_values() {
+        let max = u64::MAX;
+        let maps = MAPS
+            .iter()
+            .map(|name| format!("{} map:\n{} {} 1000", name, max - 1009, max - 999))
+            .collect::<Vec<_>>();
+        let input = format!("seeds: {} {}\n\n{}", max, max - 5, maps.join("\n\n"));
+
+        let almanac = parse_almanac(&input).unwrap();
+        assert_eq!(almanac.location(max), max - 70);
+        assert_eq!(almanac.seed(max - 70), max);
+        assert_eq!(part1(&input), (max - 75).to_string());
+    }
+
+    #[test]
+    fn errors() {
+        let replace = |from: &str, to: &str| parse_almanac(&INPUT.replace(from, to)).unwrap_err();
+
+        assert_eq!(
+            replace("50 98 2", "50 98"),
+            AlmanacError::Malformed { line: 4 }
+        );
+        assert_eq!(
+            replace("52 50 48", "52 50 4x"),
+            AlmanacError::InvalidNumber {
+                line: 5,
+                value: "4x".to_string()
+            }
+        );
+        assert_eq!(
+            replace("50 98 2", "50 18446744073709551615 2"),
+            AlmanacError::Overflow { line: 4 }
+        );
+        assert_eq!(
+            replace("52 50 48", "52 50 49"),
+            AlmanacError::Overlap { line: 4, other: 5 }
+        );
+        assert_eq!(
+            replace("water-to-light map:", "water-to-lamp map:"),
+            AlmanacError::UnknownMap {
+                line: 18,
+                name: "water-to-lamp".to_string()
+            }
+        );
+        assert_eq!(
+            replace("light-to-temperature map:", "water-to-light map:"),
+            AlmanacError::DuplicateMap {
+                line: 22,
+                name: "water-to-light".to_string(),
+                other: 18
+            }
+        );
+        assert_eq!(replace("seeds:", "plants:"), AlmanacError::MissingSeeds);
+        assert_eq!(
+            replace("seeds: 79 14 55 13", "seeds:"),
+            AlmanacError::NoSeeds
+        );
+        assert_eq!(
+            part1_checked(&INPUT.replace("79 14 55 13", "")),
+            Err(AlmanacError::NoSeeds)
+        );
+        assert_eq!(
+            part2_with_budget(
+                &INPUT.replace("79 14 55 13", "79 0 55 0"),
+                &Budget::default()
+            ),
+            Err(Error::NoLocation)
+        );
+        assert_eq!(
+            part2_with_budget(
+                &INPUT.replace("79 14 55 13", "79 14 55"),
+                &Budget::default()
+            ),
+            Err(Error::Almanac(AlmanacError::UnpairedSeeds))
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        let almanac = parse_almanac(INPUT).unwrap();
+        let segment = |source, target, length| Segment {
+            source,
+            target,
+            length,
+        };
+
+        assert_eq!(
+            almanac.maps[0].map_range(40, 70),
+            vec![
+                segment(40, 40, 10),
+                segment(50, 52, 48),
+                segment(98, 50, 2),
+                segment(100, 100, 10)
+            ]
+        );
+        assert_eq!(almanac.maps[0].map_range(79, 14), vec![segment(79, 81, 14)]);
+        assert_eq!(
+            almanac.maps[0].map_range(u64::MAX, 1),
+            vec![segment(u64::MAX, u64::MAX, 1)]
+        );
+    }
+
+    #[test]
+    fn gaps() {
+        let almanac = parse_almanac(INPUT).unwrap();
+        assert_eq!(almanac.maps[0].gaps(), vec![]);
+
+        let almanac = parse_almanac(&INPUT.replace("52 50 48", "52 50 40")).unwrap();
+        assert_eq!(almanac.maps[0].gaps(), vec![90..98]);
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(validate(INPUT), Ok(vec![]));
+
+        let gaps = validate(&INPUT.replace("52 50 48", "52 50 40")).unwrap();
+        assert_eq!(
+            gaps,
+            vec![Gap {
+                map: "seed-to-soil".to_string(),
+                values: 90..98
+            }]
+        );
+        assert_eq!(
+            gaps[0].to_string(),
+            "seed-to-soil: values 90..98 are not mapped"
+        );
+        // the unmapped seeds keep their number
+        let almanac = parse_almanac(&INPUT.replace("52 50 48", "52 50 40")).unwrap();
+        assert_eq!(almanac.maps[0].get_target(91), 91);
+    }
 }
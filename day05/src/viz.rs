@@ -0,0 +1,260 @@
+use crate::{Almanac, Segment};
+
+const WIDTH: f64 = 1000.0;
+const MARGIN: f64 = 100.0;
+const LAYER_SPACING: f64 = 80.0;
+const COLOURS: [&str; 6] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4",
+];
+
+/// "start..end", the end may be u64::MAX + 1
+fn span(start: u64, length: u64) -> String {
+    format!("{}..{}", start, u128::from(start) + u128::from(length))
+}
+
+fn offset(segment: &Segment) -> String {
+    format!("{:+}", segment.offset())
+}
+
+/// names of all categories from seed to location
+fn categories(almanac: &Almanac) -> Vec<&str> {
+    let mut categories = Vec::new();
+    for m in almanac.maps.iter() {
+        let (source, target) = m.name.split_once("-to-").unwrap_or((&m.name, &m.name));
+        if categories.is_empty() {
+            categories.push(source);
+        }
+        categories.push(target);
+    }
+    categories
+}
+
+/// the segments of every seed range for each map, following the ranges from seed to location
+fn paths(almanac: &Almanac) -> Vec<Vec<Vec<Segment>>> {
+    let seed_ranges = almanac
+        .seed_ranges()
+        .unwrap_or_else(|_| almanac.seeds.iter().map(|&seed| (seed, 1)).collect());
+
+    seed_ranges
+        .iter()
+        .map(|&range| {
+            let mut spans = vec![range];
+            almanac
+                .maps
+                .iter()
+                .map(|m| {
+                    let segments = spans
+                        .iter()
+                        .flat_map(|&(start, length)| m.map_range(start, length))
+                        .collect::<Vec<_>>();
+                    spans = segments.iter().map(|s| (s.target, s.length)).collect();
+                    segments
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// every map with its ranges and offsets, followed by the path of each seed range
+pub fn text(almanac: &Almanac) -> String {
+    let mut diagram = String::new();
+
+    for m in almanac.maps.iter() {
+        diagram += &format!("{}:\n", m.name);
+        for mapping in m.mappings.iter() {
+            let segment = Segment {
+                source: mapping.source,
+                target: mapping.target,
+                length: mapping.length,
+            };
+            diagram += &format!(
+                "  {} -> {} ({})\n",
+                span(segment.source, segment.length),
+                span(segment.target, segment.length),
+                offset(&segment)
+            );
+        }
+        for gap in m.gaps() {
+            diagram += &format!("  {}..{} unmapped\n", gap.start, gap.end);
+        }
+    }
+
+    let categories = categories(almanac);
+    for path in paths(almanac) {
+        let Some(first) = path.first() else {
+            continue;
+        };
+        let seeds = first
+            .iter()
+            .map(|s| span(s.source, s.length))
+            .collect::<Vec<_>>();
+        diagram += &format!("seeds {}:\n", seeds.join(", "));
+
+        for (category, segments) in categories.iter().skip(1).zip(path.iter()) {
+            let targets = segments
+                .iter()
+                .map(|s| format!("{} ({})", span(s.target, s.length), offset(s)))
+                .collect::<Vec<_>>();
+            diagram += &format!("  {} {}\n", category, targets.join(", "));
+        }
+    }
+
+    diagram
+}
+
+/// every category as a horizontal axis, the mapped ranges as grey bands
+/// between the axes and the seed ranges as coloured bands on top
+pub fn svg(almanac: &Almanac) -> String {
+    let categories = categories(almanac);
+    let paths = paths(almanac);
+
+    // all values are scaled to the largest end of any range
+    let max = almanac
+        .maps
+        .iter()
+        .flat_map(|m| m.mappings.iter())
+        .flat_map(|m| [m.source, m.target].map(|v| u128::from(v) + u128::from(m.length)))
+        .chain(
+            paths
+                .iter()
+                .flatten()
+                .flatten()
+                .map(|s| u128::from(s.source.max(s.target)) + u128::from(s.length)),
+        )
+        .max()
+        .unwrap_or(1)
+        .max(1) as f64;
+
+    let x = |value: u128| MARGIN + value as f64 / max * WIDTH;
+    let y = |layer: usize| MARGIN / 2.0 + layer as f64 * LAYER_SPACING;
+    let band = |segment: &Segment, layer: usize| {
+        let (source, target) = (u128::from(segment.source), u128::from(segment.target));
+        let length = u128::from(segment.length);
+        format!(
+            "{:.2},{:.2} {:.2},{:.2} {:.2},{:.2} {:.2},{:.2}",
+            x(source),
+            y(layer),
+            x(source + length),
+            y(layer),
+            x(target + length),
+            y(layer + 1),
+            x(target),
+            y(layer + 1)
+        )
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+        WIDTH + 2.0 * MARGIN,
+        y(categories.len())
+    );
+
+    for (layer, category) in categories.iter().enumerate() {
+        svg += &format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>\n",
+            MARGIN,
+            y(layer),
+            MARGIN + WIDTH,
+            y(layer)
+        );
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" font-size=\"12\">{}</text>\n",
+            MARGIN - 8.0,
+            y(layer) + 4.0,
+            category
+        );
+    }
+
+    for (layer, m) in almanac.maps.iter().enumerate() {
+        for mapping in m.mappings.iter() {
+            let segment = Segment {
+                source: mapping.source,
+                target: mapping.target,
+                length: mapping.length,
+            };
+            svg += &format!(
+                "<polygon points=\"{}\" fill=\"grey\" fill-opacity=\"0.3\"><title>{} -> {} ({})</title></polygon>\n",
+                band(&segment, layer),
+                span(segment.source, segment.length),
+                span(segment.target, segment.length),
+                offset(&segment)
+            );
+        }
+    }
+
+    for (index, path) in paths.iter().enumerate() {
+        let colour = COLOURS[index % COLOURS.len()];
+        for (layer, segments) in path.iter().enumerate() {
+            for segment in segments {
+                svg += &format!(
+                    "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"0.6\"><title>{} -> {}</title></polygon>\n",
+                    band(segment, layer),
+                    colour,
+                    span(segment.source, segment.length),
+                    span(segment.target, segment.length)
+                );
+            }
+        }
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_almanac;
+
+    const INPUT: &str = "\
+seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn diagrams() {
+        let almanac = parse_almanac(INPUT).unwrap();
+
+        let text = text(&almanac);
+        assert!(text
+            .starts_with("seed-to-soil:\n  50..98 -> 52..100 (+2)\n  98..100 -> 50..52 (-48)\n"));
+        assert!(text.contains("seeds 79..93:\n  soil 81..95 (+2)\n"));
+        // the lowest location is the answer of part 2
+        assert!(text.contains("  location 82..85 (+4), 46..56 (+0), 60..61 (+4)\n"));
+
+        let svg = svg(&almanac);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<line").count(), 8);
+        assert!(svg.contains("<title>50..98 -> 52..100 (+2)</title>"));
+    }
+}
@@ -0,0 +1,61 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// part 2 joins all races into a single one, whose record has up to four
+/// digits per race. More races than this would not fit into an `i64`
+const MAX_RACES: usize = 4;
+
+/// generates `size` races with durations of up to 99 milliseconds.
+/// `size` is capped at 4 races, like the puzzle input
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let races = (0..size.clamp(1, MAX_RACES))
+        .map(|_| {
+            let time = rng.gen_range(2..100i64);
+            // the record is always beatable by holding the button for half the time
+            let best = (time / 2) * (time - time / 2);
+            (time, rng.gen_range(0..best))
+        })
+        .collect::<Vec<_>>();
+
+    let times = races
+        .iter()
+        .map(|(t, d)| format!("{:>w$}", t, w = d.to_string().len()))
+        .collect::<Vec<_>>();
+    let distances = races
+        .iter()
+        .map(|(t, d)| format!("{:>w$}", d, w = t.to_string().len()))
+        .collect::<Vec<_>>();
+
+    format!(
+        "Time:      {}\nDistance:  {}",
+        times.join("  "),
+        distances.join("  ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beatable() {
+        let input = generate(1, 3);
+        let races = crate::parse_time_and_distances(&input);
+        assert_eq!(races.len(), 3);
+        for (time, distance) in races {
+            let best = (time / 2) * (time - time / 2);
+            assert!(distance < best, "{} {}", time, distance);
+        }
+        assert_ne!(crate::part1(&input), "0");
+        assert_ne!(crate::part2(&input), "0");
+    }
+
+    #[test]
+    fn capped() {
+        let input = generate(1, 8);
+        assert_eq!(crate::parse_time_and_distances(&input).len(), MAX_RACES);
+        // the joined race still fits and its record can be beaten
+        assert_ne!(crate::part2(&input), "0");
+    }
+}
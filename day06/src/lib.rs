@@ -1,3 +1,8 @@
+pub mod generator;
+
+use tracing::instrument;
+
+#[instrument(skip_all)]
 fn parse_time_and_distances(input: &str) -> Vec<(i64, i64)> {
     let data = input
         .lines()
@@ -10,15 +15,14 @@ fn parse_time_and_distances(input: &str) -> Vec<(i64, i64)> {
         })
         .collect::<Vec<_>>();
 
-    let mut time_and_distances = Vec::new();
-
-    for i in 0..data[0].len() {
-        time_and_distances.push((data[0][i], data[1][i]));
-    }
-
-    time_and_distances
+    data[0]
+        .iter()
+        .zip(data[1].iter())
+        .map(|(&time, &dist)| (time, dist))
+        .collect::<Vec<_>>()
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     let time_and_distances = parse_time_and_distances(input);
 
@@ -30,6 +34,7 @@ pub fn part1(input: &str) -> String {
     solutions.iter().product::<usize>().to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
     let no_spaces = input.replace(' ', "");
     let time_and_distances = parse_time_and_distances(&no_spaces);
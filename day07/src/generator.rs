@@ -0,0 +1,35 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const CARDS: &[u8] = b"23456789TJQKA";
+
+/// generates `size` hands with bets between 1 and 1000
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let hands = (0..size)
+        .map(|_| {
+            let hand = (0..5)
+                .map(|_| CARDS[rng.gen_range(0..CARDS.len())] as char)
+                .collect::<String>();
+            format!("{} {}", hand, rng.gen_range(1..=1000))
+        })
+        .collect::<Vec<_>>();
+
+    hands.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hands() {
+        let tournament =
+            crate::Tournament::parse(&generate(1, 100), &crate::Rules::standard()).unwrap();
+        assert_eq!(tournament.hands.len(), 100);
+        for hand in tournament.hands.iter() {
+            assert_eq!(hand.cards.len(), 5);
+            assert!((1..=1000).contains(&hand.bet));
+        }
+    }
+}
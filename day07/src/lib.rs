@@ -1,226 +1,668 @@
-use std::collections::HashSet;
-
-#[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
-enum Card {
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Ten,
-    Joker,
-    Queen,
-    King,
-    Ace,
-}
-
-impl From<char> for Card {
-    fn from(value: char) -> Self {
+pub mod generator;
+
+use serde::Serialize;
+use std::{cmp::Ordering, collections::HashMap, error, fmt};
+use tracing::instrument;
+
+/// problems with a single card of a hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardError {
+    /// the rank is not part of the order of the rules
+    InvalidRank(char),
+    InvalidSuit(char),
+    MissingSuit,
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardError::InvalidRank(c) => write!(f, "invalid card '{}'", c),
+            CardError::InvalidSuit(c) => write!(f, "invalid suit '{}'", c),
+            CardError::MissingSuit => write!(f, "the last card has no suit"),
+        }
+    }
+}
+
+impl error::Error for CardError {}
+
+/// suit of a card in suited rules, written as 'c', 'd', 'h' or 's'.
+/// Ranks have no such type, which ranks exist depends on the order of the `Rules`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl TryFrom<char> for Suit {
+    type Error = CardError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'J' => Card::Joker,
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            _ => Card::Ace,
-        }
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
-enum Type {
-    HighCard([Card; 5]),
-    OnePair([Card; 5]),
-    TwoPair([Card; 5]),
-    ThreeOfAKind([Card; 5]),
-    FullHouse([Card; 5]),
-    FourOfAKind([Card; 5]),
-    FiveOfAKind([Card; 5]),
-}
-
-impl From<&str> for Type {
-    fn from(value: &str) -> Self {
-        let cards: [Card; 5] = value
-            .chars()
-            .map(|c| c.into())
-            .collect::<Vec<_>>()
-            .try_into()
-            .expect("should be length 5");
-
-        let unique = cards.iter().collect::<HashSet<_>>();
-        let mut occurrences = unique
+            'c' => Ok(Suit::Clubs),
+            'd' => Ok(Suit::Diamonds),
+            'h' => Ok(Suit::Hearts),
+            's' => Ok(Suit::Spades),
+            _ => Err(CardError::InvalidSuit(value)),
+        }
+    }
+}
+
+/// problems with a line of the input, lines start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandError {
+    Malformed {
+        line: usize,
+    },
+    Card {
+        line: usize,
+        hand: String,
+        error: CardError,
+    },
+    /// bets have to be positive integers
+    InvalidBet {
+        line: usize,
+        bet: String,
+    },
+}
+
+impl fmt::Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HandError::Malformed { line } => write!(f, "line {}: expected cards and bet", line),
+            HandError::Card { line, hand, error } => {
+                write!(f, "line {}: {} in hand \"{}\"", line, error, hand)
+            }
+            HandError::InvalidBet { line, bet } => {
+                write!(f, "line {}: invalid bet \"{}\"", line, bet)
+            }
+        }
+    }
+}
+
+impl error::Error for HandError {}
+
+/// problems with self made rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RulesError {
+    /// without any type hands can't be classified
+    EmptyLadder,
+    DuplicateRank(char),
+    /// the wild card is not part of the order
+    UnknownWildcard(char),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::EmptyLadder => write!(f, "the rules need at least one type"),
+            RulesError::DuplicateRank(c) => write!(f, "rank '{}' appears twice in the order", c),
+            RulesError::UnknownWildcard(c) => {
+                write!(f, "wild card '{}' is not part of the order", c)
+            }
+        }
+    }
+}
+
+impl error::Error for RulesError {}
+
+/// a combination of cards a hand can form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// groups of cards with the same rank, e.g. `[3, 2]` for a full house.
+    /// `[1]` matches every hand
+    Groups(Vec<usize>),
+    /// all ranks follow each other in the order of the rules.
+    /// Wild ranks are left out of that order, so with jokers "9TQKA" is a straight
+    Straight,
+    /// all cards have the same suit
+    Flush,
+    StraightFlush,
+}
+
+/// a step of the type ladder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kind {
+    pub name: String,
+    pub pattern: Pattern,
+}
+
+impl Kind {
+    pub fn new(name: &str, pattern: Pattern) -> Self {
+        Kind {
+            name: name.to_string(),
+            pattern,
+        }
+    }
+
+    pub fn groups(name: &str, groups: &[usize]) -> Self {
+        Kind::new(name, Pattern::Groups(groups.to_vec()))
+    }
+}
+
+/// the types of Camel Cards from the weakest to the strongest
+pub fn camel_ladder() -> Vec<Kind> {
+    vec![
+        Kind::groups("high card", &[1]),
+        Kind::groups("one pair", &[2]),
+        Kind::groups("two pair", &[2, 2]),
+        Kind::groups("three of a kind", &[3]),
+        Kind::groups("full house", &[3, 2]),
+        Kind::groups("four of a kind", &[4]),
+        Kind::groups("five of a kind", &[5]),
+    ]
+}
+
+/// Camel Cards extended by straights and flushes like in poker
+pub fn poker_ladder() -> Vec<Kind> {
+    vec![
+        Kind::groups("high card", &[1]),
+        Kind::groups("one pair", &[2]),
+        Kind::groups("two pair", &[2, 2]),
+        Kind::groups("three of a kind", &[3]),
+        Kind::new("straight", Pattern::Straight),
+        Kind::new("flush", Pattern::Flush),
+        Kind::groups("full house", &[3, 2]),
+        Kind::groups("four of a kind", &[4]),
+        Kind::new("straight flush", Pattern::StraightFlush),
+        Kind::groups("five of a kind", &[5]),
+    ]
+}
+
+/// which cards are wild, how the cards are ordered and which types exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// all ranks from the weakest to the strongest
+    order: Vec<char>,
+    /// a wild card counts as whatever card makes the best type
+    wildcards: Vec<char>,
+    /// all types from the weakest to the strongest, never empty
+    ladder: Vec<Kind>,
+    /// cards are written as rank and suit, e.g. "Ah"
+    suited: bool,
+}
+
+impl Rules {
+    /// rules with the ranks of `order` from the weakest to the strongest
+    pub fn new(
+        order: &str,
+        wildcards: &[char],
+        ladder: Vec<Kind>,
+        suited: bool,
+    ) -> Result<Self, RulesError> {
+        let order = order.chars().collect::<Vec<_>>();
+
+        if ladder.is_empty() {
+            return Err(RulesError::EmptyLadder);
+        }
+        if let Some((_, &rank)) = order
             .iter()
-            .map(|&c| cards.iter().filter(|&card| *card == *c).count())
+            .enumerate()
+            .find(|&(i, rank)| order[..i].contains(rank))
+        {
+            return Err(RulesError::DuplicateRank(rank));
+        }
+        if let Some(&wildcard) = wildcards.iter().find(|w| !order.contains(w)) {
+            return Err(RulesError::UnknownWildcard(wildcard));
+        }
+
+        Ok(Rules {
+            order,
+            wildcards: wildcards.to_vec(),
+            ladder,
+            suited,
+        })
+    }
+
+    /// the rules of part 1
+    pub fn standard() -> Self {
+        Rules {
+            order: "23456789TJQKA".chars().collect(),
+            wildcards: Vec::new(),
+            ladder: camel_ladder(),
+            suited: false,
+        }
+    }
+
+    /// the rules of part 2, J is wild but the weakest card on its own
+    pub fn jokers() -> Self {
+        Rules {
+            order: "J23456789TQKA".chars().collect(),
+            wildcards: vec!['J'],
+            ..Rules::standard()
+        }
+    }
+
+    /// position of the rank in the order
+    fn strength(&self, rank: char) -> Result<usize, CardError> {
+        self.order
+            .iter()
+            .position(|&c| c == rank)
+            .ok_or(CardError::InvalidRank(rank))
+    }
+
+    fn is_wild(&self, rank: char) -> bool {
+        self.wildcards.contains(&rank)
+    }
+
+    /// all ranks which are not wild from the weakest to the strongest
+    fn natural_ranks(&self) -> Vec<char> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|&r| !self.is_wild(r))
+            .collect()
+    }
+
+    /// the ranks of the cards after replacing the wild cards,
+    /// so that they form the pattern
+    fn resolve(&self, cards: &[(char, Option<Suit>)], pattern: &Pattern) -> String {
+        let natural = cards
+            .iter()
+            .map(|(r, _)| *r)
+            .filter(|&r| !self.is_wild(r))
             .collect::<Vec<_>>();
+        let ranks = self.natural_ranks();
+        let strongest = ranks.last().copied().unwrap_or('?');
+
+        let mut replacements = match pattern {
+            Pattern::Groups(required) => {
+                let mut frequencies = HashMap::new();
+                for &rank in natural.iter() {
+                    *frequencies.entry(rank).or_insert(0) += 1;
+                }
+                // most common ranks first, the stronger one on a draw
+                let mut groups = frequencies.into_iter().collect::<Vec<_>>();
+                groups.sort_by_key(|&(rank, count)| {
+                    (
+                        std::cmp::Reverse(count),
+                        std::cmp::Reverse(self.strength(rank).ok()),
+                    )
+                });
+                let mut unused = ranks.iter().rev().filter(|r| !natural.contains(r)).copied();
+
+                let mut required = required.clone();
+                required.sort_by(|a, b| b.cmp(a));
+                required
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, &size)| {
+                        let (rank, count) = match groups.get(i) {
+                            Some(&group) => group,
+                            None => (unused.next().unwrap_or(strongest), 0),
+                        };
+                        std::iter::repeat_n(rank, size.saturating_sub(count))
+                    })
+                    .collect::<Vec<_>>()
+            }
+            Pattern::Straight | Pattern::StraightFlush => {
+                let positions = natural
+                    .iter()
+                    .filter_map(|r| ranks.iter().position(|c| c == r))
+                    .collect::<Vec<_>>();
+                let length = cards.len().min(ranks.len());
+                let start = positions
+                    .iter()
+                    .min()
+                    .copied()
+                    .unwrap_or(ranks.len())
+                    .min(ranks.len() - length);
+
+                (start..start + length)
+                    .filter(|p| !positions.contains(p))
+                    .map(|p| ranks[p])
+                    .collect()
+            }
+            Pattern::Flush => Vec::new(),
+        }
+        .into_iter();
+
+        // wild cards that are not needed for the pattern join the first replacement
+        let surplus = replacements.clone().next().unwrap_or(strongest);
+        cards
+            .iter()
+            .map(|&(r, _)| {
+                if self.is_wild(r) {
+                    replacements.next().unwrap_or(surplus)
+                } else {
+                    r
+                }
+            })
+            .collect()
+    }
+
+    /// splits a hand into its cards as (rank, suit)
+    fn cards(&self, hand: &str) -> Result<Vec<(char, Option<Suit>)>, CardError> {
+        let chars = hand.chars().collect::<Vec<_>>();
+        if !self.suited {
+            return Ok(chars.into_iter().map(|c| (c, None)).collect());
+        }
+
+        chars
+            .chunks(2)
+            .map(|c| match c {
+                [rank, suit] => Ok((*rank, Some(Suit::try_from(*suit)?))),
+                _ => Err(CardError::MissingSuit),
+            })
+            .collect()
+    }
+}
+
+/// a type of the ladder, `level` is its position from the weakest type
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Type {
+    pub level: usize,
+    pub name: String,
+}
+
+impl Type {
+    /// the strongest type of the ladder the cards can form
+    fn classify(cards: &[(char, Option<Suit>)], rules: &Rules) -> Self {
+        let (wild, natural): (Vec<_>, Vec<_>) = cards.iter().partition(|(r, _)| rules.is_wild(*r));
+        let wild = wild.len();
 
+        let mut frequencies = HashMap::new();
+        for (rank, _) in natural.iter() {
+            *frequencies.entry(*rank).or_insert(0) += 1;
+        }
+        let mut occurrences = frequencies.values().copied().collect::<Vec<_>>();
         occurrences.sort_by(|a, b| b.cmp(a));
-        match (occurrences.first(), occurrences.get(1)) {
-            (Some(5), _) => Type::FiveOfAKind(cards),
-            (Some(4), _) => Type::FourOfAKind(cards),
-            (Some(3), Some(2)) => Type::FullHouse(cards),
-            (Some(3), Some(1)) => Type::ThreeOfAKind(cards),
-            (Some(2), Some(2)) => Type::TwoPair(cards),
-            (Some(2), Some(1)) => Type::OnePair(cards),
-            _ => Type::HighCard(cards),
+
+        // the wild cards fill the gaps of the largest groups first
+        let groups = |required: &[usize]| {
+            let mut required = required.to_vec();
+            required.sort_by(|a, b| b.cmp(a));
+            let missing = required
+                .iter()
+                .enumerate()
+                .map(|(i, r)| r.saturating_sub(occurrences.get(i).copied().unwrap_or(0)))
+                .sum::<usize>();
+            required.iter().sum::<usize>() <= cards.len() && missing <= wild
+        };
+
+        let straight = || {
+            // wild cards can't be used as their own rank
+            let ranks = rules
+                .order
+                .iter()
+                .filter(|&&r| !rules.is_wild(r))
+                .collect::<Vec<_>>();
+            let positions = natural
+                .iter()
+                .map(|(r, _)| ranks.iter().position(|&&c| c == *r))
+                .collect::<Option<Vec<_>>>();
+
+            match positions {
+                Some(positions) if frequencies.len() == natural.len() => {
+                    let span = match (positions.iter().min(), positions.iter().max()) {
+                        (Some(min), Some(max)) => max - min + 1,
+                        _ => 0,
+                    };
+                    cards.len() <= ranks.len() && span <= cards.len()
+                }
+                _ => false,
+            }
+        };
+
+        let flush = || {
+            rules.suited && {
+                let suits = natural.iter().map(|(_, s)| *s).collect::<Vec<_>>();
+                suits.iter().all(|s| s.is_some() && *s == suits[0])
+            }
+        };
+
+        let level = rules
+            .ladder
+            .iter()
+            .rposition(|kind| match &kind.pattern {
+                Pattern::Groups(required) => groups(required),
+                Pattern::Straight => straight(),
+                Pattern::Flush => flush(),
+                Pattern::StraightFlush => straight() && flush(),
+            })
+            .unwrap_or(0);
+
+        // `Rules::new` makes sure the ladder has a type at level 0
+        Type {
+            level,
+            name: rules.ladder[level].name.clone(),
         }
     }
 }
 
+/// a hand with its bet, ordered by type and then card by card.
+/// Hands are equal if they rank the same, the bet and the resolved cards don't matter
 #[derive(Debug)]
-struct Hand {
-    cards: Type,
-    bet: usize,
-}
-
-#[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
-enum JCard {
-    Joker,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Ten,
-    Queen,
-    King,
-    Ace,
-}
-
-impl From<char> for JCard {
-    fn from(value: char) -> Self {
-        match value {
-            '2' => JCard::Two,
-            '3' => JCard::Three,
-            '4' => JCard::Four,
-            '5' => JCard::Five,
-            '6' => JCard::Six,
-            '7' => JCard::Seven,
-            '8' => JCard::Eight,
-            '9' => JCard::Nine,
-            'T' => JCard::Ten,
-            'J' => JCard::Joker,
-            'Q' => JCard::Queen,
-            'K' => JCard::King,
-            _ => JCard::Ace,
-        }
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
-enum JType {
-    HighCard([JCard; 5]),
-    OnePair([JCard; 5]),
-    TwoPair([JCard; 5]),
-    ThreeOfAKind([JCard; 5]),
-    FullHouse([JCard; 5]),
-    FourOfAKind([JCard; 5]),
-    FiveOfAKind([JCard; 5]),
-}
-
-impl From<&str> for JType {
-    fn from(value: &str) -> Self {
-        let cards: [JCard; 5] = value
-            .chars()
-            .map(|c| c.into())
-            .collect::<Vec<_>>()
-            .try_into()
-            .expect("should be length 5");
-
-        let unique = cards.iter().collect::<HashSet<_>>();
-        let mut occurrences = unique
+pub struct Hand {
+    pub cards: String,
+    pub kind: Type,
+    /// the ranks with the wild cards replaced by the cards they stand for
+    pub resolved: String,
+    strengths: Vec<usize>,
+    pub bet: usize,
+}
+
+impl Hand {
+    pub fn new(cards: &str, bet: usize, rules: &Rules) -> Result<Self, CardError> {
+        let parsed = rules.cards(cards)?;
+        let strengths = parsed
             .iter()
-            .map(|&c| cards.iter().filter(|&card| *card == *c).count())
-            .collect::<Vec<_>>();
+            .map(|(r, _)| rules.strength(*r))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let joker_amount = cards.iter().filter(|&card| *card == JCard::Joker).count();
+        let kind = Type::classify(&parsed, rules);
+        let resolved = rules.resolve(&parsed, &rules.ladder[kind.level].pattern);
 
-        occurrences.sort_by(|a, b| b.cmp(a));
-        match (occurrences.first(), occurrences.get(1), joker_amount) {
-            (Some(5), _, _) => JType::FiveOfAKind(cards),
-            (Some(4), _, 4) => JType::FiveOfAKind(cards),
-            (Some(4), _, 1) => JType::FiveOfAKind(cards),
-            (Some(4), _, 0) => JType::FourOfAKind(cards),
-            (Some(3), Some(2), 3) => JType::FiveOfAKind(cards),
-            (Some(3), Some(2), 2) => JType::FiveOfAKind(cards),
-            (Some(3), Some(2), 0) => JType::FullHouse(cards),
-            (Some(3), Some(1), 3) => JType::FourOfAKind(cards),
-            (Some(3), Some(1), 1) => JType::FourOfAKind(cards),
-            (Some(3), Some(1), 0) => JType::ThreeOfAKind(cards),
-            (Some(2), Some(2), 2) => JType::FourOfAKind(cards),
-            (Some(2), Some(2), 1) => JType::FullHouse(cards),
-            (Some(2), Some(2), 0) => JType::TwoPair(cards),
-            (Some(2), Some(1), 2) => JType::ThreeOfAKind(cards),
-            (Some(2), Some(1), 1) => JType::ThreeOfAKind(cards),
-            (Some(2), Some(1), 0) => JType::OnePair(cards),
-            (Some(1), _, 1) => JType::OnePair(cards),
-            _ => JType::HighCard(cards),
+        Ok(Hand {
+            cards: cards.to_string(),
+            kind,
+            resolved,
+            strengths,
+            bet,
+        })
+    }
+
+    /// the position of the first card (starting at 1) that decides between
+    /// two hands of the same type, `None` if the types differ or the cards are equal
+    pub fn tie_break(&self, other: &Hand) -> Option<usize> {
+        if self.kind != other.kind {
+            return None;
         }
+
+        self.strengths
+            .iter()
+            .zip(other.strengths.iter())
+            .position(|(a, b)| a != b)
+            .map(|p| p + 1)
+    }
+}
+
+/// a line of the ranking report
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Standing {
+    pub rank: usize,
+    pub cards: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub resolved: String,
+    pub bet: usize,
+    pub winnings: usize,
+    /// the card that put this hand ahead of the hand ranked right below it
+    pub tie_break: Option<usize>,
+}
+
+impl fmt::Display for Standing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "rank {}: {} {}", self.rank, self.cards, self.kind)?;
+        if self.resolved != self.cards {
+            write!(f, " as {}", self.resolved)?;
+        }
+        write!(f, ", bet {}, winnings {}", self.bet, self.winnings)?;
+        if let Some(position) = self.tie_break {
+            write!(f, ", ahead of rank {} by card {}", self.rank - 1, position)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.kind
+            .cmp(&other.kind)
+            .then_with(|| self.strengths.cmp(&other.strengths))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
+/// hands sorted from the lowest to the highest rank
 #[derive(Debug)]
-struct JHand {
-    cards: JType,
-    bet: usize,
+pub struct Tournament {
+    pub hands: Vec<Hand>,
 }
 
-pub fn part1(input: &str) -> String {
-    let mut hands = input
-        .lines()
-        .map(|line| {
-            let (h, b) = line.split_once(' ').unwrap();
-
-            Hand {
-                cards: h.into(),
-                bet: b.parse::<usize>().unwrap(),
-            }
-        })
-        .collect::<Vec<_>>();
+impl Tournament {
+    pub fn new(hands: impl IntoIterator<Item = Hand>) -> Self {
+        let mut hands = hands.into_iter().collect::<Vec<_>>();
+        hands.sort();
+        Tournament { hands }
+    }
 
-    hands.sort_by(|a, b| a.cards.cmp(&b.cards));
+    /// parses "cards bet" lines
+    #[instrument(skip_all)]
+    pub fn parse(input: &str, rules: &Rules) -> Result<Self, HandError> {
+        let hands = input
+            .lines()
+            .enumerate()
+            .map(|(index, l)| {
+                let line = index + 1;
+                let (h, b) = l.split_once(' ').ok_or(HandError::Malformed { line })?;
+                // `parse` would also accept a leading '+'
+                let bet = Some(b)
+                    .filter(|b| b.bytes().all(|c| c.is_ascii_digit()))
+                    .and_then(|b| b.parse::<usize>().ok())
+                    .filter(|&bet| bet > 0)
+                    .ok_or_else(|| HandError::InvalidBet {
+                        line,
+                        bet: b.to_string(),
+                    })?;
 
-    let winnings = hands
-        .iter()
-        .enumerate()
-        .map(|(index, hand)| (index + 1) * hand.bet);
+                Hand::new(h, bet, rules).map_err(|error| HandError::Card {
+                    line,
+                    hand: h.to_string(),
+                    error,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-    winnings.sum::<usize>().to_string()
+        Ok(Tournament::new(hands))
+    }
+
+    /// every hand with its rank (starting at 1) and winnings
+    pub fn standings(&self) -> impl Iterator<Item = (usize, &Hand, usize)> {
+        self.hands
+            .iter()
+            .enumerate()
+            .map(|(index, hand)| (index + 1, hand, (index + 1) * hand.bet))
+    }
+
+    /// sum of the bets multiplied with their rank
+    pub fn winnings(&self) -> usize {
+        self.standings().map(|(_, _, w)| w).sum()
+    }
+
+    /// all hands from the lowest to the highest rank with the reason for their rank
+    pub fn report(&self) -> Vec<Standing> {
+        self.standings()
+            .map(|(rank, hand, winnings)| Standing {
+                rank,
+                cards: hand.cards.clone(),
+                kind: hand.kind.name.clone(),
+                resolved: hand.resolved.clone(),
+                bet: hand.bet,
+                winnings,
+                tie_break: rank
+                    .checked_sub(2)
+                    .and_then(|below| hand.tie_break(&self.hands[below])),
+            })
+            .collect()
+    }
 }
 
+#[instrument(skip_all)]
+pub fn part1(input: &str) -> String {
+    part1_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
-    let mut hands = input
-        .lines()
-        .map(|line| {
-            let (h, b) = line.split_once(' ').unwrap();
-
-            JHand {
-                cards: h.into(),
-                bet: b.parse::<usize>().unwrap(),
-            }
-        })
-        .collect::<Vec<_>>();
+    part2_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// like `part1`, but reports invalid hands instead of panicking
+pub fn part1_checked(input: &str) -> Result<String, HandError> {
+    Ok(Tournament::parse(input, &Rules::standard())?
+        .winnings()
+        .to_string())
+}
+
+/// like `part2`, but reports invalid hands instead of panicking
+pub fn part2_checked(input: &str) -> Result<String, HandError> {
+    Ok(Tournament::parse(input, &Rules::jokers())?
+        .winnings()
+        .to_string())
+}
+
+/// lists all hands ordered by their rank, once without and once with jokers
+pub fn explain(input: &str) -> String {
+    let mut explanation = String::new();
+
+    for (title, rules) in [
+        ("without jokers:\n", Rules::standard()),
+        ("with jokers:\n", Rules::jokers()),
+    ] {
+        let tournament = match Tournament::parse(input, &rules) {
+            Ok(tournament) => tournament,
+            Err(e) => return format!("{}\n", e),
+        };
+
+        explanation += title;
+        for standing in tournament.report() {
+            explanation += &format!("{}\n", standing);
+        }
+    }
 
-    hands.sort_by(|a, b| a.cards.cmp(&b.cards));
+    explanation
+}
 
-    let winnings = hands
-        .iter()
-        .enumerate()
-        .map(|(index, hand)| (index + 1) * hand.bet);
+/// the reports of both rulesets
+#[derive(Debug, Serialize)]
+struct Report {
+    without_jokers: Vec<Standing>,
+    with_jokers: Vec<Standing>,
+}
 
-    winnings.sum::<usize>().to_string()
+/// like `explain`, but as a JSON object with the reports of both rulesets
+pub fn explain_json(input: &str) -> Result<String, HandError> {
+    let report = Report {
+        without_jokers: Tournament::parse(input, &Rules::standard())?.report(),
+        with_jokers: Tournament::parse(input, &Rules::jokers())?.report(),
+    };
+
+    Ok(serde_json::to_string_pretty(&report).expect("standings are serializable"))
 }
 
 #[cfg(test)]
@@ -245,4 +687,243 @@ QQQJA 483";
         let result = part2(INPUT);
         assert_eq!(result, "5905");
     }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT);
+        assert!(result.contains(
+            "rank 5: QQQJA three of a kind, bet 483, winnings 2415, ahead of rank 4 by card 1\n"
+        ));
+        assert!(result.contains("rank 5: KTJJT four of a kind as KTTTT, bet 220, winnings 1100, ahead of rank 4 by card 1\n"));
+        assert!(result
+            .contains("rank 3: KK677 two pair, bet 28, winnings 84, ahead of rank 2 by card 2\n"));
+    }
+
+    fn kind(cards: &str, rules: &Rules) -> String {
+        Hand::new(cards, 1, rules).unwrap().kind.name
+    }
+
+    #[test]
+    fn wildcards() {
+        let rules = Rules::jokers();
+        for (cards, name) in [
+            ("JJJJJ", "five of a kind"),
+            ("JJJJ2", "five of a kind"),
+            ("JJ234", "three of a kind"),
+            ("J2234", "three of a kind"),
+            ("J2233", "full house"),
+            ("J2345", "one pair"),
+            ("23456", "high card"),
+        ] {
+            assert_eq!(kind(cards, &rules), name, "{}", cards);
+        }
+
+        // a different wild card and order need no new types
+        let rules = Rules {
+            wildcards: vec!['2'],
+            ..Rules::standard()
+        };
+        assert_eq!(kind("2KK3Q", &rules), "three of a kind");
+        let hand = |cards| Hand::new(cards, 1, &rules).unwrap();
+        assert!(hand("22KKK") > hand("33KKK"));
+        assert!(hand("2KKKK") > hand("22KKK"));
+
+        // equality agrees with the ordering, the bet does not count
+        let rules = Rules::jokers();
+        let a = Hand::new("JKKK2", 1, &rules).unwrap();
+        let b = Hand::new("JKKK2", 5, &rules).unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+        assert_eq!(a, b);
+        assert_ne!(a, Hand::new("KJKK2", 1, &rules).unwrap());
+
+        // several wild cards
+        let rules = Rules {
+            wildcards: vec!['J', '2'],
+            ..Rules::standard()
+        };
+        assert_eq!(kind("J2KK3", &rules), "four of a kind");
+        assert_eq!(kind("J2345", &rules), "three of a kind");
+    }
+
+    #[test]
+    fn hand_sizes() {
+        let rules = Rules {
+            ladder: vec![
+                Kind::groups("high card", &[1]),
+                Kind::groups("pair", &[2]),
+                Kind::groups("two triples", &[3, 3]),
+                Kind::groups("seven of a kind", &[7]),
+            ],
+            ..Rules::jokers()
+        };
+        assert_eq!(kind("AAAKKKJ", &rules), "two triples");
+        assert_eq!(kind("AAJKKK2", &rules), "two triples");
+        assert_eq!(kind("AAAAAAJ", &rules), "seven of a kind");
+        assert_eq!(kind("AK", &rules), "high card");
+        assert_eq!(kind("AAAAA", &rules), "pair");
+
+        let tournament = Tournament::parse("AAAKKKJ 10\nAAAAAAJ 1\n2345678 100", &rules).unwrap();
+        assert_eq!(tournament.winnings(), 100 + 2 * 10 + 3);
+    }
+
+    #[test]
+    fn straights_and_flushes() {
+        let rules = Rules {
+            ladder: poker_ladder(),
+            ..Rules::jokers()
+        };
+        assert_eq!(kind("23456", &rules), "straight");
+        assert_eq!(kind("2345J", &rules), "straight");
+        assert_eq!(kind("2346J", &rules), "straight");
+        assert_eq!(kind("2347J", &rules), "one pair");
+        assert_eq!(kind("TQKAJ", &rules), "straight");
+        // J is left out of the order, so Q follows T
+        assert_eq!(kind("9TQKA", &rules), "straight");
+        let standard = Rules {
+            ladder: poker_ladder(),
+            ..Rules::standard()
+        };
+        assert_eq!(kind("9TQKA", &standard), "high card");
+        assert_eq!(kind("9TJQK", &standard), "straight");
+
+        let suited = Rules {
+            suited: true,
+            ..rules
+        };
+        assert_eq!(kind("2h5h9hKhAh", &suited), "flush");
+        assert_eq!(kind("2h3h4h5hJs", &suited), "straight flush");
+        assert_eq!(kind("2h3h4h5h6s", &suited), "straight");
+        assert_eq!(kind("AhAsAdAcJc", &suited), "five of a kind");
+
+        let tournament =
+            Tournament::parse("2h3h4h5h6s 1\n2h5h9hKhAh 2\n3h3s3d9c9h 3", &suited).unwrap();
+        assert_eq!(
+            tournament
+                .standings()
+                .map(|(rank, hand, _)| (rank, hand.kind.name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "straight"), (2, "flush"), (3, "full house")]
+        );
+    }
+
+    #[test]
+    fn invalid_hands() {
+        let parse = |input: &str| Tournament::parse(input, &Rules::standard()).unwrap_err();
+
+        let error = parse("32T3K 765\nT55X5 684");
+        assert_eq!(
+            error,
+            HandError::Card {
+                line: 2,
+                hand: "T55X5".to_string(),
+                error: CardError::InvalidRank('X')
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 2: invalid card 'X' in hand \"T55X5\""
+        );
+        assert_eq!(Suit::try_from('h'), Ok(Suit::Hearts));
+        assert_eq!(Suit::try_from('H'), Err(CardError::InvalidSuit('H')));
+
+        assert_eq!(
+            parse("32T3K 0"),
+            HandError::InvalidBet {
+                line: 1,
+                bet: "0".to_string()
+            }
+        );
+        assert_eq!(
+            parse("32T3K -5"),
+            HandError::InvalidBet {
+                line: 1,
+                bet: "-5".to_string()
+            }
+        );
+        assert_eq!(
+            parse("32T3K +5"),
+            HandError::InvalidBet {
+                line: 1,
+                bet: "+5".to_string()
+            }
+        );
+        assert_eq!(parse("32T3K"), HandError::Malformed { line: 1 });
+        assert!(part2_checked("32t3K 765").is_err());
+
+        let suited = Rules {
+            suited: true,
+            ..Rules::standard()
+        };
+        assert_eq!(
+            Hand::new("2h3x", 1, &suited),
+            Err(CardError::InvalidSuit('x'))
+        );
+        assert_eq!(Hand::new("2h3", 1, &suited), Err(CardError::MissingSuit));
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert_eq!(
+            Rules::new("23456789TJQKA", &[], Vec::new(), false),
+            Err(RulesError::EmptyLadder)
+        );
+        assert_eq!(
+            Rules::new("2345662", &[], camel_ladder(), false),
+            Err(RulesError::DuplicateRank('6'))
+        );
+        assert_eq!(
+            Rules::new("23456", &['J'], camel_ladder(), false),
+            Err(RulesError::UnknownWildcard('J'))
+        );
+
+        let rules = Rules::new("J23456789TQKA", &['J'], poker_ladder(), false).unwrap();
+        assert_eq!(kind("2345J", &rules), "straight");
+    }
+
+    #[test]
+    fn resolved_wildcards() {
+        let resolve = |cards, rules: &Rules| Hand::new(cards, 1, rules).unwrap().resolved;
+
+        let rules = Rules::jokers();
+        assert_eq!(resolve("T55J5", &rules), "T5555");
+        assert_eq!(resolve("JJ234", &rules), "44234");
+        assert_eq!(resolve("J2233", &rules), "32233");
+        assert_eq!(resolve("JJJJJ", &rules), "AAAAA");
+        assert_eq!(resolve("23456", &rules), "23456");
+
+        let rules = Rules {
+            ladder: poker_ladder(),
+            ..Rules::jokers()
+        };
+        assert_eq!(resolve("2346J", &rules), "23465");
+        assert_eq!(resolve("QKAJT", &rules), "QKA9T");
+    }
+
+    #[test]
+    fn report() {
+        let tournament = Tournament::parse(INPUT, &Rules::jokers()).unwrap();
+        let report = tournament.report();
+        assert_eq!(
+            report[1],
+            Standing {
+                rank: 2,
+                cards: "KK677".to_string(),
+                kind: "two pair".to_string(),
+                resolved: "KK677".to_string(),
+                bet: 28,
+                winnings: 56,
+                tie_break: None,
+            }
+        );
+        assert_eq!(report[4].tie_break, Some(1));
+
+        let json = explain_json(INPUT).unwrap();
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(value["with_jokers"][4]["resolved"], "KTTTT");
+        assert_eq!(value["without_jokers"][0]["type"], "one pair");
+        assert_eq!(
+            value["without_jokers"][0]["tie_break"],
+            serde_json::Value::Null
+        );
+    }
 }
@@ -0,0 +1,163 @@
+use crate::{Cycle, Network, Node};
+use serde::Serialize;
+
+fn join(steps: &[usize]) -> String {
+    steps
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// the network as a Graphviz digraph. Start nodes are green, goal nodes red
+/// and every start node is annotated with the cycle of its ghost
+pub fn dot(network: &Network, ghosts: &[(Node, Cycle)]) -> String {
+    let (starts, goals) = (network.ends_with('A'), network.ends_with('Z'));
+
+    let mut graph = String::from("digraph network {\n");
+
+    for node in network.nodes() {
+        let name = network.name(node);
+        let mut attributes = Vec::new();
+
+        if starts[node as usize] {
+            attributes.push("style=filled, fillcolor=palegreen".to_string());
+        } else if goals[node as usize] {
+            attributes.push("style=filled, fillcolor=salmon".to_string());
+        }
+
+        if let Some((_, cycle)) = ghosts.iter().find(|(start, _)| *start == node) {
+            let mut label = format!(
+                "{}\\ncycle from step {}, length {}\\ngoals at {}",
+                name,
+                cycle.start,
+                cycle.length,
+                join(&cycle.goals)
+            );
+            if !cycle.goals_before.is_empty() {
+                label += &format!("\\nbefore the cycle at {}", join(&cycle.goals_before));
+            }
+            attributes.push(format!("label=\"{}\"", label));
+        }
+
+        if attributes.is_empty() {
+            graph += &format!("  \"{}\";\n", name);
+        } else {
+            graph += &format!("  \"{}\" [{}];\n", name, attributes.join(", "));
+        }
+    }
+
+    for node in network.nodes() {
+        let [left, right] = network.neighbours(node);
+        for (target, label) in [(left, "L"), (right, "R")] {
+            graph += &format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                network.name(node),
+                network.name(target),
+                label
+            );
+        }
+    }
+
+    graph += "}\n";
+    graph
+}
+
+#[derive(Debug, Serialize)]
+struct JsonNode<'a> {
+    name: &'a str,
+    left: &'a str,
+    right: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonGhost<'a> {
+    start: &'a str,
+    cycle_start: usize,
+    cycle_length: usize,
+    goals_before: &'a [usize],
+    goals: &'a [usize],
+}
+
+#[derive(Debug, Serialize)]
+struct JsonNetwork<'a> {
+    instructions: String,
+    nodes: Vec<JsonNode<'a>>,
+    ghosts: Vec<JsonGhost<'a>>,
+}
+
+/// the instructions, the adjacency of every node and the cycles of the ghosts as JSON
+pub fn json(network: &Network, ghosts: &[(Node, Cycle)]) -> String {
+    let export = JsonNetwork {
+        instructions: network
+            .instructions
+            .iter()
+            .map(|&i| if i == 0 { 'L' } else { 'R' })
+            .collect(),
+        nodes: network
+            .nodes()
+            .map(|node| {
+                let [left, right] = network.neighbours(node);
+                JsonNode {
+                    name: network.name(node),
+                    left: network.name(left),
+                    right: network.name(right),
+                }
+            })
+            .collect(),
+        ghosts: ghosts
+            .iter()
+            .map(|(start, cycle)| JsonGhost {
+                start: network.name(*start),
+                cycle_start: cycle.start,
+                cycle_length: cycle.length,
+                goals_before: &cycle.goals_before,
+                goals: &cycle.goals,
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&export).expect("the network is serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ghosts;
+    use budget::Budget;
+
+    const INPUT: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn exports() {
+        let network = Network::parse(INPUT).unwrap();
+        let ghosts = ghosts(&network, &Budget::default()).unwrap();
+
+        let graph = dot(&network, &ghosts);
+        assert!(graph.starts_with("digraph network {\n"));
+        assert!(graph.contains(
+            "  \"22A\" [style=filled, fillcolor=palegreen, label=\"22A\\ncycle from step 1, length 6\\ngoals at 3, 6\"];\n"
+        ));
+        assert!(graph.contains("  \"11Z\" [style=filled, fillcolor=salmon];\n"));
+        assert!(graph.contains("  \"11B\" -> \"11Z\" [label=\"R\"];\n"));
+        assert_eq!(graph.matches("->").count(), 16);
+
+        let json = json(&network, &ghosts);
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(value["instructions"], "LR");
+        assert_eq!(value["nodes"][0]["name"], "11A");
+        assert_eq!(value["nodes"][0]["left"], "11B");
+        assert_eq!(value["ghosts"][1]["start"], "22A");
+        assert_eq!(value["ghosts"][1]["cycle_length"], 6);
+    }
+}
@@ -0,0 +1,116 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+const PRIMES: [usize; 10] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+const GHOSTS: usize = 6;
+
+/// name for the nth inner node, it never ends with 'A' or 'Z'
+fn inner_name(mut index: usize) -> String {
+    let mut name = vec![b'B' + (index % 24) as u8];
+    index /= 24;
+    while name.len() < 3 || index > 0 {
+        name.push(b'A' + (index % 26) as u8);
+        index /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+/// generates a network with `size` instructions and 6 ghosts.
+/// like the puzzle input every ghost walks a cycle that is a multiple of the
+/// instruction length and only hits its goal at the end of that cycle.
+/// the first ghost starts at "AAA" and ends on "ZZZ".
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+
+    let instructions = (0..size)
+        .map(|_| if rng.gen_bool(0.5) { 'L' } else { 'R' })
+        .collect::<Vec<_>>();
+
+    let mut primes = PRIMES.to_vec();
+    primes.shuffle(&mut rng);
+
+    let mut nodes = Vec::new();
+    let mut inner = 0;
+
+    for (ghost, &multiple) in primes.iter().take(GHOSTS).enumerate() {
+        let prefix = ((b'A' + ghost as u8) as char).to_string().repeat(2);
+        let (start, goal) = if ghost == 0 {
+            ("AAA".to_string(), "ZZZ".to_string())
+        } else {
+            (format!("{}A", prefix), format!("{}Z", prefix))
+        };
+
+        // the path of the ghost: start, the inner nodes of the cycle and the goal
+        let length = multiple * size;
+        let mut path = vec![start];
+        for _ in 1..length {
+            path.push(inner_name(inner));
+            inner += 1;
+        }
+        path.push(goal);
+
+        for (index, node) in path.iter().enumerate() {
+            // after the goal the ghost continues with the first inner node
+            let next = if index == length {
+                &path[1]
+            } else {
+                &path[index + 1]
+            };
+            // the direction not taken leads to a random node of the same path
+            let decoy = &path[rng.gen_range(0..path.len())];
+
+            let (left, right) = match instructions[index % size] {
+                'L' => (next, decoy),
+                _ => (decoy, next),
+            };
+            nodes.push(format!("{} = ({}, {})", node, left, right));
+        }
+    }
+
+    nodes.shuffle(&mut rng);
+
+    format!(
+        "{}\n\n{}",
+        instructions.iter().collect::<String>(),
+        nodes.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles() {
+        let size = 10;
+        let network = crate::Network::parse(&generate(1, size)).unwrap();
+        let ghosts = crate::ghosts(&network, &budget::Budget::unlimited()).unwrap();
+        assert_eq!(ghosts.len(), GHOSTS);
+
+        for (_, cycle) in ghosts {
+            // the goal is only reached at the end of every cycle
+            assert_eq!(cycle.length % size, 0);
+            assert!(PRIMES.contains(&(cycle.length / size)));
+            assert!(cycle.goals_before.is_empty());
+            assert_eq!(cycle.goals, vec![cycle.start + cycle.length - 1]);
+        }
+    }
+
+    #[test]
+    fn large() {
+        // only the visited states are stored, not every state of the network
+        let size = 2000;
+        let network = crate::Network::parse(&generate(1, size)).unwrap();
+        let goal = network.node("ZZZ").unwrap();
+        let cycle = crate::find_cycle(
+            &network,
+            network.node("AAA").unwrap(),
+            |n| n == goal,
+            &budget::Budget::unlimited(),
+        )
+        .unwrap();
+        assert_eq!(cycle.length % size, 0);
+        assert_eq!(cycle.goals, vec![cycle.start + cycle.length - 1]);
+    }
+}
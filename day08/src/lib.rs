@@ -1,104 +1,490 @@
+pub mod export;
+pub mod generator;
+
+use budget::{Budget, Exhausted};
 use num_integer::Integer;
-use std::collections::HashMap;
+use std::{collections::HashMap, error, fmt};
+use tracing::{debug, instrument};
 
-use aoc_parse::{parser, prelude::*};
+/// index of a node in the network
+pub type Node = u32;
 
-#[derive(Debug, Clone)]
-enum Dir {
-    Left,
-    Right,
+/// problems found while parsing the network, lines start at 1
+#[derive(Debug, PartialEq, Eq)]
+pub enum NetworkError {
+    MissingInstructions,
+    InvalidInstruction {
+        position: usize,
+        instruction: char,
+    },
+    Malformed {
+        line: usize,
+    },
+    /// names consist of ASCII letters and digits only
+    InvalidName {
+        line: usize,
+        name: String,
+    },
+    Duplicate {
+        line: usize,
+        name: String,
+    },
+    UnknownNode {
+        line: usize,
+        name: String,
+    },
+    MissingNode {
+        name: String,
+    },
 }
 
-struct MapIterator<'a> {
-    position: &'a str,
-    steps: Box<dyn Iterator<Item = Dir>>,
-    maps: &'a HashMap<String, (String, String)>,
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkError::MissingInstructions => write!(f, "the instructions are missing"),
+            NetworkError::InvalidInstruction {
+                position,
+                instruction,
+            } => write!(
+                f,
+                "invalid instruction '{}' at position {}",
+                instruction, position
+            ),
+            NetworkError::Malformed { line } => {
+                write!(f, "line {}: expected \"AAA = (BBB, CCC)\"", line)
+            }
+            NetworkError::InvalidName { line, name } => {
+                write!(f, "line {}: invalid node name \"{}\"", line, name)
+            }
+            NetworkError::Duplicate { line, name } => {
+                write!(f, "line {}: node {} is defined twice", line, name)
+            }
+            NetworkError::UnknownNode { line, name } => {
+                write!(f, "line {}: node {} is not defined", line, name)
+            }
+            NetworkError::MissingNode { name } => write!(f, "node {} is missing", name),
+        }
+    }
 }
 
-impl<'a> MapIterator<'a> {
-    fn new(start: &'a str, steps: &[Dir], maps: &'a HashMap<String, (String, String)>) -> Self {
-        MapIterator {
-            position: start,
-            // allowed, because this seems to be a known issue
-            // https://rust-lang.github.io/rust-clippy/master/index.html#/unnecessary_to_owned
-            #[allow(clippy::unnecessary_to_owned)]
-            steps: Box::new(steps.to_owned().into_iter().cycle()),
-            maps,
+impl error::Error for NetworkError {}
+
+/// reasons for a part to stop without an answer
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Network(NetworkError),
+    Budget(Exhausted),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Network(e) => e.fmt(f),
+            Error::Budget(e) => e.fmt(f),
         }
     }
 }
 
-impl<'a> Iterator for MapIterator<'a> {
-    type Item = &'a str;
-    fn next(&mut self) -> Option<Self::Item> {
-        let s = self.steps.next().unwrap();
-        match s {
-            Dir::Left => self.position = &self.maps.get(self.position).unwrap().0,
-            Dir::Right => self.position = &self.maps.get(self.position).unwrap().1,
+impl error::Error for Error {}
+
+impl From<NetworkError> for Error {
+    fn from(value: NetworkError) -> Self {
+        Error::Network(value)
+    }
+}
+
+impl From<Exhausted> for Error {
+    fn from(value: Exhausted) -> Self {
+        Error::Budget(value)
+    }
+}
+
+/// the desert network with all node names interned into indices
+#[derive(Debug)]
+pub struct Network {
+    names: Vec<String>,
+    indices: HashMap<String, Node>,
+    /// left and right neighbour of every node
+    edges: Vec<[Node; 2]>,
+    /// 0 for left and 1 for right
+    pub instructions: Vec<u8>,
+}
+
+impl Network {
+    #[instrument(skip_all)]
+    pub fn parse(input: &str) -> Result<Self, NetworkError> {
+        let mut lines = input.lines().enumerate().map(|(index, l)| (index + 1, l));
+
+        let instructions = lines
+            .next()
+            .map(|(_, l)| l)
+            .filter(|l| !l.is_empty())
+            .ok_or(NetworkError::MissingInstructions)?
+            .chars()
+            .enumerate()
+            .map(|(position, instruction)| match instruction {
+                'L' => Ok(0),
+                'R' => Ok(1),
+                _ => Err(NetworkError::InvalidInstruction {
+                    position: position + 1,
+                    instruction,
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // the names are interned first, the neighbours are resolved afterwards
+        let mut definitions = Vec::new();
+        let mut indices = HashMap::new();
+        for (line, l) in lines.filter(|(_, l)| !l.is_empty()) {
+            let (name, left, right) = l
+                .split_once(" = (")
+                .and_then(|(name, rest)| {
+                    let (left, right) = rest.strip_suffix(')')?.split_once(", ")?;
+                    Some((name, left, right))
+                })
+                .ok_or(NetworkError::Malformed { line })?;
+
+            if let Some(invalid) = [name, left, right]
+                .into_iter()
+                .find(|n| n.is_empty() || !n.chars().all(|c| c.is_ascii_alphanumeric()))
+            {
+                return Err(NetworkError::InvalidName {
+                    line,
+                    name: invalid.to_string(),
+                });
+            }
+
+            if indices
+                .insert(name.to_string(), definitions.len() as Node)
+                .is_some()
+            {
+                return Err(NetworkError::Duplicate {
+                    line,
+                    name: name.to_string(),
+                });
+            }
+            definitions.push((line, name, left, right));
+        }
+
+        let edges = definitions
+            .iter()
+            .map(|&(line, _, left, right)| {
+                let resolve = |name: &str| {
+                    indices
+                        .get(name)
+                        .copied()
+                        .ok_or_else(|| NetworkError::UnknownNode {
+                            line,
+                            name: name.to_string(),
+                        })
+                };
+                Ok([resolve(left)?, resolve(right)?])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let names = definitions
+            .iter()
+            .map(|&(_, name, _, _)| name.to_string())
+            .collect();
+
+        Ok(Network {
+            names,
+            indices,
+            edges,
+            instructions,
+        })
+    }
+
+    /// amount of nodes
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn node(&self, name: &str) -> Result<Node, NetworkError> {
+        self.indices
+            .get(name)
+            .copied()
+            .ok_or_else(|| NetworkError::MissingNode {
+                name: name.to_string(),
+            })
+    }
+
+    pub fn name(&self, node: Node) -> &str {
+        &self.names[node as usize]
+    }
+
+    /// all nodes in the order they were defined
+    pub fn nodes(&self) -> impl Iterator<Item = Node> {
+        0..self.len() as Node
+    }
+
+    /// left and right neighbour of a node
+    pub fn neighbours(&self, node: Node) -> [Node; 2] {
+        self.edges[node as usize]
+    }
+
+    /// the node reached from `node` with the instruction used at `step`
+    pub fn step(&self, node: Node, step: usize) -> Node {
+        let instruction = self.instructions[step % self.instructions.len()];
+        self.edges[node as usize][instruction as usize]
+    }
+
+    /// the node reached after following `count` instructions from `node`
+    pub fn advance(&self, mut node: Node, count: usize) -> Node {
+        for instruction in self.instructions.iter().cycle().take(count) {
+            node = self.edges[node as usize][*instruction as usize];
         }
+        node
+    }
 
-        Some(self.position)
+    /// endless iterator over the nodes visited from `start`
+    pub fn walk(&self, start: Node) -> Walk<'_> {
+        Walk {
+            network: self,
+            node: start,
+            step: 0,
+        }
+    }
+
+    /// for every node whether its name ends with the given character
+    pub fn ends_with(&self, c: char) -> Vec<bool> {
+        self.names.iter().map(|n| n.ends_with(c)).collect()
     }
 }
 
-fn parse_input(input: &str) -> (Vec<Dir>, HashMap<String, (String, String)>) {
-    let (steps, maps) = input.split_once("\n\n").unwrap();
-    let i_parser = parser!(line({
-        'L' => Dir::Left,
-        'R' => Dir::Right,
-    }+));
+pub struct Walk<'a> {
+    network: &'a Network,
+    node: Node,
+    /// index of the next instruction
+    step: usize,
+}
 
-    let steps = i_parser.parse(steps).unwrap();
+impl Iterator for Walk<'_> {
+    type Item = Node;
 
-    let m_parser = parser!(lines(string(alnum+) " = (" string(alnum+) ", " string(alnum+) ")"));
-    let maps = m_parser.parse(maps).unwrap();
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction = self.network.instructions[self.step];
+        self.node = self.network.edges[self.node as usize][instruction as usize];
+        self.step += 1;
+        if self.step == self.network.instructions.len() {
+            self.step = 0;
+        }
 
-    let maps = maps
-        .into_iter()
-        .map(|(s, l, r)| (s, (l, r)))
-        .collect::<HashMap<_, _>>();
+        Some(self.node)
+    }
+}
+
+/// counts the steps from `start` until `goal` is true for the current location.
+/// the budget is checked on every step, because the goal might never be reached
+#[instrument(skip(network, goal, budget))]
+fn steps_until(
+    network: &Network,
+    start: Node,
+    goal: impl Fn(Node) -> bool,
+    budget: &Budget,
+) -> Result<usize, Exhausted> {
+    for (count, location) in network.walk(start).enumerate() {
+        budget.step()?;
+        if goal(location) {
+            debug!(
+                location = network.name(location),
+                steps = count + 1,
+                "goal reached"
+            );
+            return Ok(count + 1);
+        }
+    }
 
-    (steps, maps)
+    unreachable!("the walk is endless")
 }
 
 pub fn part1(input: &str) -> String {
-    let (steps, maps) = parse_input(input);
+    part1_with_budget(input, &Budget::unlimited()).unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[instrument(skip_all)]
+pub fn part1_with_budget(input: &str, budget: &Budget) -> Result<String, Error> {
+    let network = Network::parse(input)?;
+    let (start, goal) = (network.node("AAA")?, network.node("ZZZ")?);
+
+    let count = steps_until(&network, start, |location| location == goal, budget)?;
+
+    Ok(count.to_string())
+}
+
+/// the path of a ghost ends up in a cycle, because the state (location and
+/// position in the instructions) repeats at some point.
+/// all times are steps counted from the start
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cycle {
+    /// first step of the cycle
+    pub start: usize,
+    pub length: usize,
+    /// steps before the cycle starts at which the ghost is on a goal
+    pub goals_before: Vec<usize>,
+    /// steps of the first pass through the cycle at which the ghost is on a goal
+    pub goals: Vec<usize>,
+}
 
-    let (count, _) = MapIterator::new("AAA", &steps, &maps)
-        .enumerate()
-        .find(|&(_, location)| location == "ZZZ")
-        .unwrap();
+impl Cycle {
+    fn at_goal(&self, step: usize) -> bool {
+        if step < self.start {
+            return self.goals_before.contains(&step);
+        }
 
-    (count + 1).to_string()
+        let offset = self.start + (step - self.start) % self.length;
+        self.goals.contains(&offset)
+    }
+}
+
+/// walks from `start` until a state repeats
+#[instrument(skip(network, goal, budget))]
+pub fn find_cycle(
+    network: &Network,
+    start: Node,
+    goal: impl Fn(Node) -> bool,
+    budget: &Budget,
+) -> Result<Cycle, Exhausted> {
+    // the step at which every state was first seen, states are stored
+    // as location * instruction length + instruction index
+    let instructions = network.instructions.len();
+    let mut seen = HashMap::new();
+    seen.insert(start as usize * instructions, 0);
+    let mut goals = Vec::new();
+
+    for (count, location) in network.walk(start).enumerate() {
+        budget.step()?;
+        let step = count + 1;
+        let state = location as usize * instructions + step % instructions;
+
+        if let Some(&first) = seen.get(&state) {
+            let (goals_before, goals) = goals.into_iter().partition(|&g| g < first);
+            debug!(start = first, length = step - first, "cycle found");
+            return Ok(Cycle {
+                start: first,
+                length: step - first,
+                goals_before,
+                goals,
+            });
+        }
+
+        seen.insert(state, step);
+        if goal(location) {
+            goals.push(step);
+        }
+    }
+
+    unreachable!("the walk is endless")
+}
+
+/// the first step at which all ghosts are on a goal at the same time
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Alignment {
+    Step(u128),
+    Never,
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Alignment::Step(step) => write!(f, "{}", step),
+            Alignment::Never => write!(f, "never aligned"),
+        }
+    }
+}
+
+/// combines x = r1 (mod m1) and x = r2 (mod m2) into a single congruence,
+/// the moduli don't have to be coprime
+fn crt((r1, m1): (i128, i128), (r2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let gcd = m1.extended_gcd(&m2);
+    let g = gcd.gcd;
+    if (r2 - r1) % g != 0 {
+        return None;
+    }
+
+    // gcd.x is the inverse of m1 / g modulo m2 / g
+    let lcm = m1 / g * m2;
+    let k = ((r2 - r1) / g % (m2 / g)) * gcd.x % (m2 / g);
+    let x = (r1 + m1 * k).rem_euclid(lcm);
+
+    Some((x, lcm))
+}
+
+/// the first step (at least 1) at which every ghost is on a goal
+#[instrument(skip_all)]
+pub fn align(cycles: &[Cycle], budget: &Budget) -> Result<Alignment, Exhausted> {
+    // before all ghosts are inside their cycle the steps are checked one by one
+    let latest = cycles.iter().map(|c| c.start).max().unwrap_or(0);
+    for step in 1..latest {
+        budget.step()?;
+        if cycles.iter().all(|c| c.at_goal(step)) {
+            return Ok(Alignment::Step(step as u128));
+        }
+    }
+
+    // afterwards every combination of goals gives a congruence for each ghost
+    let mut congruences = vec![(0i128, 1i128)];
+    for c in cycles {
+        let mut combined = Vec::new();
+        for &congruence in congruences.iter() {
+            for &goal in c.goals.iter() {
+                budget.step()?;
+                combined.extend(crt(congruence, (goal as i128, c.length as i128)));
+            }
+        }
+        congruences = combined;
+    }
+
+    let first = congruences
+        .into_iter()
+        .map(|(r, m)| {
+            // the first solution which is not before any cycle starts
+            let latest = (latest as i128).max(1);
+            let periods = ((latest - r).max(0) + m - 1) / m;
+            r + periods * m
+        })
+        .min();
+
+    Ok(match first {
+        Some(step) => Alignment::Step(step as u128),
+        None => Alignment::Never,
+    })
 }
 
 pub fn part2(input: &str) -> String {
-    let (steps, maps) = parse_input(input);
+    part2_with_budget(input, &Budget::unlimited()).unwrap_or_else(|e| panic!("{}", e))
+}
 
-    // let mut count: usize = 0;
-    let locations = maps
-        .keys()
-        .filter_map(|k| k.ends_with('A').then_some(k.as_str()))
-        .collect::<Vec<_>>();
+/// the cycle of every ghost starting on a node ending with 'A'
+/// and looking for nodes ending with 'Z'
+pub fn ghosts(network: &Network, budget: &Budget) -> Result<Vec<(Node, Cycle)>, Exhausted> {
+    let (starts, goals) = (network.ends_with('A'), network.ends_with('Z'));
 
-    // while testing with the actual input I noticed all "paths" have a fixed cycle length
-    // so figuring out the first time a path hits its goal is enough to figure out
-    // at what point in time all paths are on their respective goal
-    let cycles = locations
-        .iter()
-        .map(|&start| {
-            let (count, _) = MapIterator::new(start, &steps, &maps)
-                .enumerate()
-                .find(|&(_, loc)| loc.ends_with('Z'))
-                .unwrap();
-            count + 1
+    network
+        .nodes()
+        .filter(|&n| starts[n as usize])
+        .map(|start| {
+            let cycle = find_cycle(network, start, |n| goals[n as usize], budget)?;
+            Ok((start, cycle))
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    // calculate the lowest common multiple of all cycles
-    let lcm = cycles.into_iter().reduce(|acc, el| acc.lcm(&el)).unwrap();
+/// analyses the cycle of every ghost and combines them with the chinese remainder theorem
+#[instrument(skip_all)]
+pub fn part2_with_budget(input: &str, budget: &Budget) -> Result<String, Error> {
+    let network = Network::parse(input)?;
 
-    lcm.to_string()
+    let cycles = ghosts(&network, budget)?
+        .into_iter()
+        .map(|(_, cycle)| cycle)
+        .collect::<Vec<_>>();
+
+    Ok(align(&cycles, budget)?.to_string())
 }
 
 #[cfg(test)]
@@ -135,4 +521,139 @@ XXX = (XXX, XXX)";
         let result = part2(INPUT2);
         assert_eq!(result, "6");
     }
+
+    #[test]
+    fn p1_unreachable() {
+        let input = "\
+L
+
+AAA = (BBB, BBB)
+BBB = (AAA, AAA)
+ZZZ = (ZZZ, ZZZ)";
+        let result = part1_with_budget(input, &Budget::unlimited().with_steps(1000));
+        assert_eq!(result, Err(Error::Budget(Exhausted::Steps)));
+    }
+
+    #[test]
+    fn cycles() {
+        let network = Network::parse(INPUT2).unwrap();
+        let goal = network.node("22Z").unwrap();
+        let cycle = find_cycle(
+            &network,
+            network.node("22A").unwrap(),
+            |n| n == goal,
+            &Budget::default(),
+        );
+        assert_eq!(
+            cycle,
+            Ok(Cycle {
+                start: 1,
+                length: 6,
+                goals_before: vec![],
+                goals: vec![3, 6]
+            })
+        );
+    }
+
+    #[test]
+    fn p2_offsets() {
+        // the first goals are at 2 and 1, but the ghosts meet at 4, not at 2
+        let input = "\
+L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11Z, 11Z)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)";
+        let result = part2(input);
+        assert_eq!(result, "4");
+    }
+
+    #[test]
+    fn p2_never() {
+        // one ghost is on its goal at even steps, the other one at odd steps
+        let input = "\
+L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)";
+        let result = part2(input);
+        assert_eq!(result, "never aligned");
+    }
+
+    #[test]
+    fn remainders() {
+        assert_eq!(crt((2, 4), (3, 6)), None);
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((0, 1), (5, 7)), Some((5, 7)));
+    }
+
+    #[test]
+    fn network() {
+        let network = Network::parse(INPUT).unwrap();
+        assert_eq!(network.len(), 3);
+        let (a, b, z) = (
+            network.node("AAA").unwrap(),
+            network.node("BBB").unwrap(),
+            network.node("ZZZ").unwrap(),
+        );
+        assert_eq!(network.neighbours(b), [a, z]);
+        assert_eq!(network.walk(a).take(6).last(), Some(z));
+        assert_eq!(network.advance(a, 6), z);
+        assert_eq!(network.step(b, 2), z);
+        assert_eq!(network.name(z), "ZZZ");
+    }
+
+    #[test]
+    fn errors() {
+        let parse = |input: &str| Network::parse(input).unwrap_err();
+
+        assert_eq!(
+            parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)"),
+            NetworkError::UnknownNode {
+                line: 4,
+                name: "ZZZ".to_string()
+            }
+        );
+        assert_eq!(
+            parse("LLR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)"),
+            NetworkError::Duplicate {
+                line: 4,
+                name: "AAA".to_string()
+            }
+        );
+        assert_eq!(
+            parse("LLR\n\nAAA = AAA, AAA"),
+            NetworkError::Malformed { line: 3 }
+        );
+        assert_eq!(
+            parse("LLR\n\nAAA = (AAA, A\"A)"),
+            NetworkError::InvalidName {
+                line: 3,
+                name: "A\"A".to_string()
+            }
+        );
+        assert_eq!(
+            parse("LXR\n\nAAA = (AAA, AAA)"),
+            NetworkError::InvalidInstruction {
+                position: 2,
+                instruction: 'X'
+            }
+        );
+        assert_eq!(parse(""), NetworkError::MissingInstructions);
+        assert_eq!(
+            part1_with_budget("L\n\nAAA = (AAA, AAA)", &Budget::default()),
+            Err(Error::Network(NetworkError::MissingNode {
+                name: "ZZZ".to_string()
+            }))
+        );
+    }
 }
@@ -0,0 +1,43 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// generates `size` histories of 21 values, each produced by a random
+/// polynomial of degree 0 to 5 with small integer coefficients
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let histories = (0..size)
+        .map(|_| {
+            let coefficients = (0..=rng.gen_range(0..=5))
+                .map(|_| rng.gen_range(-9..=9i64))
+                .collect::<Vec<_>>();
+
+            (0..21i64)
+                .map(|x| {
+                    coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |acc, c| acc * x + c)
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>();
+
+    histories.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degrees() {
+        let input = generate(1, 100);
+        assert!(input.lines().all(|l| l.split(' ').count() == 21));
+
+        let histories = crate::parse_histories(&input).unwrap();
+        assert_eq!(histories.len(), 100);
+        assert!(histories.iter().all(|h| h.degree().unwrap_or(0) <= 5));
+    }
+}
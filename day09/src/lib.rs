@@ -1,93 +1,297 @@
-struct HistorySteps {
-    values: Vec<i64>,
-    init: bool,
-    done: bool,
-    forward: bool,
-}
-
-impl HistorySteps {
-    fn new(values: Vec<i64>, forward: bool) -> Self {
-        HistorySteps {
-            values,
-            init: true,
-            done: false,
-            forward,
+pub mod generator;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+use std::{collections::BTreeMap, error, fmt};
+use tracing::instrument;
+
+/// problems with a history, lines start at 1
+#[derive(Debug, PartialEq, Eq)]
+pub enum HistoryError {
+    InvalidNumber {
+        line: usize,
+        value: String,
+    },
+    /// less than two values, so there is not even a single difference
+    TooShort {
+        line: usize,
+    },
+    /// the differences never became all zero before running out of values
+    NeverZero {
+        line: usize,
+    },
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HistoryError::InvalidNumber { line, value } => {
+                write!(f, "line {}: invalid number \"{}\"", line, value)
+            }
+            HistoryError::TooShort { line } => {
+                write!(f, "line {}: a history needs at least two values", line)
+            }
+            HistoryError::NeverZero { line } => {
+                write!(f, "line {}: the differences never reach zero", line)
+            }
         }
     }
 }
 
-impl Iterator for HistorySteps {
-    type Item = i64;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.init {
-            self.init = false;
-            if self.forward {
-                return Some(*self.values.last().unwrap());
-            } else {
-                return Some(*self.values.first().unwrap());
+impl error::Error for HistoryError {}
+
+/// a history in Newton's forward difference form:
+/// value(x) = sum of differences[k] * binomial(x, k)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Newton {
+    /// the first value of every difference row until the row of zeros
+    pub differences: Vec<BigInt>,
+    /// amount of values in the history
+    pub len: usize,
+}
+
+/// binomial coefficient for any integer `x`, also negative ones
+fn binomial(x: &BigInt, k: usize) -> BigInt {
+    // every intermediate result is a binomial coefficient itself,
+    // so the divisions are exact
+    (0..k).fold(BigInt::one(), |acc, j| acc * (x - j) / (j + 1))
+}
+
+impl Newton {
+    /// builds the difference rows until one of them is all zero.
+    /// `None` if the values run out before that happens
+    pub fn fit(values: &[BigInt]) -> Option<Self> {
+        let mut differences = Vec::new();
+        let mut row = values.to_vec();
+
+        loop {
+            if row.is_empty() {
+                return None;
+            }
+            if row.iter().all(|v| v.is_zero()) {
+                break;
             }
+
+            differences.push(row[0].clone());
+            row = row.windows(2).map(|w| &w[1] - &w[0]).collect();
         }
-        if self.done {
-            return None;
+
+        Some(Newton {
+            differences,
+            len: values.len(),
+        })
+    }
+
+    /// degree of the generating polynomial, `None` for a history of zeros
+    pub fn degree(&self) -> Option<usize> {
+        self.differences.len().checked_sub(1)
+    }
+
+    /// the value at `index`, the history starts at 0
+    pub fn at(&self, index: &BigInt) -> BigInt {
+        self.differences
+            .iter()
+            .enumerate()
+            .map(|(k, d)| d * binomial(index, k))
+            .sum()
+    }
+
+    /// the value `steps` after the last value of the history
+    pub fn forward(&self, steps: u64) -> BigInt {
+        self.at(&(BigInt::from(self.len) - 1 + steps))
+    }
+
+    /// the value `steps` before the first value of the history
+    pub fn backward(&self, steps: u64) -> BigInt {
+        self.at(&-BigInt::from(steps))
+    }
+
+    /// the generating polynomial with one coefficient per power of x
+    pub fn polynomial(&self) -> Polynomial {
+        let mut coefficients = vec![BigRational::zero(); self.differences.len()];
+        // x(x-1)...(x-k+1), the numerator of binomial(x, k)
+        let mut falling = vec![BigInt::one()];
+        let mut factorial = BigInt::one();
+
+        for (k, d) in self.differences.iter().enumerate() {
+            if k > 0 {
+                factorial *= k;
+                let mut next = vec![BigInt::zero(); k + 1];
+                for (i, c) in falling.iter().enumerate() {
+                    next[i + 1] += c;
+                    next[i] -= c * (k - 1);
+                }
+                falling = next;
+            }
+
+            for (i, c) in falling.iter().enumerate() {
+                coefficients[i] += BigRational::new(d * c, factorial.clone());
+            }
         }
 
-        self.values = self
-            .values
+        Polynomial { coefficients }
+    }
+}
+
+/// a polynomial with rational coefficients, starting at the constant term
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Polynomial {
+    pub coefficients: Vec<BigRational>,
+}
+
+impl Polynomial {
+    /// `None` for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.iter().rposition(|c| !c.is_zero())
+    }
+
+    /// the value at any rational `x`, the history starts at 0
+    pub fn at(&self, x: &BigRational) -> BigRational {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigRational::zero(), |acc, c| acc * x + c)
+    }
+}
+
+/// "1/3 x^3 - x^2 + 11/3 x + 10"
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms = self
+            .coefficients
             .iter()
-            .zip(self.values.iter().skip(1))
-            .map(|(&a, &b)| b - a)
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| !c.is_zero())
             .collect::<Vec<_>>();
-        if self.values.iter().all(|i| *i == 0) {
-            self.done = true;
+
+        if terms.is_empty() {
+            return write!(f, "0");
         }
 
-        if self.forward {
-            return Some(*self.values.last().unwrap());
+        for (n, &(power, c)) in terms.iter().enumerate() {
+            let sign = match (n, c.is_negative()) {
+                (0, true) => "-",
+                (0, false) => "",
+                (_, true) => " - ",
+                (_, false) => " + ",
+            };
+            let magnitude = c.abs();
+            let coefficient = match power {
+                0 => magnitude.to_string(),
+                _ if magnitude.is_one() => String::new(),
+                _ => format!("{} ", magnitude),
+            };
+            let variable = match power {
+                0 => String::new(),
+                1 => "x".to_string(),
+                _ => format!("x^{}", power),
+            };
+            write!(f, "{}{}{}", sign, coefficient, variable)?;
         }
 
-        Some(*self.values.first().unwrap())
+        Ok(())
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<i64>> {
+#[instrument(skip_all)]
+pub fn parse_histories(input: &str) -> Result<Vec<Newton>, HistoryError> {
     input
         .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|n| n.parse::<i64>().expect("should only be a number"))
-                .collect::<Vec<_>>()
+        .enumerate()
+        .map(|(index, l)| {
+            let line = index + 1;
+            let values = l
+                .split_ascii_whitespace()
+                .map(|n| {
+                    n.parse::<BigInt>()
+                        .map_err(|_| HistoryError::InvalidNumber {
+                            line,
+                            value: n.to_string(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.len() < 2 {
+                return Err(HistoryError::TooShort { line });
+            }
+
+            Newton::fit(&values).ok_or(HistoryError::NeverZero { line })
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
-    let oasis = parse_input(input);
+    part1_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
 
-    let extrapolations = oasis.into_iter().map(|v| {
-        let last_numbers = HistorySteps::new(v, true).collect::<Vec<_>>();
-        last_numbers
-            .into_iter()
-            .rev()
-            .reduce(|acc, el| acc + el)
-            .unwrap()
-    });
+#[instrument(skip_all)]
+pub fn part2(input: &str) -> String {
+    part2_checked(input).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// like `part1`, but reports histories that can't be extrapolated
+pub fn part1_checked(input: &str) -> Result<String, HistoryError> {
+    let histories = parse_histories(input)?;
 
-    extrapolations.sum::<i64>().to_string()
+    let extrapolations = histories.iter().map(|h| h.forward(1));
+
+    Ok(extrapolations.sum::<BigInt>().to_string())
 }
 
-pub fn part2(input: &str) -> String {
-    let oasis = parse_input(input);
+/// like `part2`, but reports histories that can't be extrapolated
+pub fn part2_checked(input: &str) -> Result<String, HistoryError> {
+    let histories = parse_histories(input)?;
 
-    let extrapolations = oasis.into_iter().map(|v| {
-        let first_numbers = HistorySteps::new(v, false).collect::<Vec<_>>();
-        first_numbers
-            .into_iter()
-            .rev()
-            .reduce(|acc, el| el - acc)
-            .unwrap()
-    });
+    let extrapolations = histories.iter().map(|h| h.backward(1));
+
+    Ok(extrapolations.sum::<BigInt>().to_string())
+}
 
-    extrapolations.sum::<i64>().to_string()
+/// how many histories are generated by a polynomial of each degree,
+/// histories of zeros are counted as "none"
+pub fn degrees(histories: &[Newton]) -> BTreeMap<Option<usize>, usize> {
+    let mut degrees = BTreeMap::new();
+    for h in histories {
+        *degrees.entry(h.degree()).or_insert(0) += 1;
+    }
+    degrees
+}
+
+/// lists the polynomial and both extrapolated values of every history,
+/// followed by the amount of histories per degree
+pub fn explain(input: &str) -> String {
+    let histories = match parse_histories(input) {
+        Ok(histories) => histories,
+        Err(e) => return format!("{}\n", e),
+    };
+
+    let describe = |degree: Option<usize>| match degree {
+        Some(degree) => degree.to_string(),
+        None => "none".to_string(),
+    };
+
+    let mut explanation = histories
+        .iter()
+        .enumerate()
+        .map(|(index, h)| {
+            format!(
+                "line {}: degree {}, {}, next {}, previous {}\n",
+                index + 1,
+                describe(h.degree()),
+                h.polynomial(),
+                h.forward(1),
+                h.backward(1)
+            )
+        })
+        .collect::<String>();
+
+    for (degree, amount) in degrees(&histories) {
+        explanation += &format!("degree {}: {} histories\n", describe(degree), amount);
+    }
+
+    explanation
 }
 
 #[cfg(test)]
@@ -110,4 +314,108 @@ mod tests {
         let result = part2(INPUT);
         assert_eq!(result, "2");
     }
+
+    #[test]
+    fn extrapolation() {
+        let histories = parse_histories(INPUT).unwrap();
+        let h = &histories[2];
+        assert_eq!(h.degree(), Some(3));
+        assert_eq!(h.forward(1), BigInt::from(68));
+        assert_eq!(h.forward(2), BigInt::from(101));
+        assert_eq!(h.backward(1), BigInt::from(5));
+        // 10 + 3x + x(x-1)(x-2)/3 at x = -3
+        assert_eq!(h.backward(3), BigInt::from(-19));
+
+        let zeros = parse_histories("0 0 0").unwrap();
+        assert_eq!(zeros[0].degree(), None);
+        assert_eq!(zeros[0].forward(100), BigInt::zero());
+    }
+
+    #[test]
+    fn large_values() {
+        let h = &parse_histories("1 1").unwrap()[0];
+        assert_eq!(h.degree(), Some(0));
+        assert_eq!(h.forward(u64::MAX), BigInt::one());
+
+        // x^2 at x = u64::MAX + 3 needs more than 128 bits
+        let h = &parse_histories("0 1 4 9").unwrap()[0];
+        let x = BigInt::from(u64::MAX) + 3;
+        assert_eq!(h.forward(u64::MAX), &x * &x);
+
+        let h = &parse_histories("-9223372036854775808 0 9223372036854775808").unwrap()[0];
+        assert_eq!(h.forward(1).to_string(), "18446744073709551616");
+    }
+
+    #[test]
+    fn never_zero() {
+        assert_eq!(
+            parse_histories("0 3 6\n1 2 4 8"),
+            Err(HistoryError::NeverZero { line: 2 })
+        );
+        assert_eq!(
+            part1_checked("1 2"),
+            Err(HistoryError::NeverZero { line: 1 })
+        );
+        assert_eq!(parse_histories(""), Ok(vec![]));
+        assert_eq!(
+            parse_histories("0 3 6\n\n1 2 3"),
+            Err(HistoryError::TooShort { line: 2 })
+        );
+        assert_eq!(
+            part2_checked("5").unwrap_err().to_string(),
+            "line 1: a history needs at least two values"
+        );
+        assert_eq!(
+            parse_histories("1 x"),
+            Err(HistoryError::InvalidNumber {
+                line: 1,
+                value: "x".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn polynomials() {
+        let histories = parse_histories(INPUT).unwrap();
+        let p = histories[2].polynomial();
+        assert_eq!(p.degree(), Some(3));
+        assert_eq!(p.to_string(), "1/3 x^3 - x^2 + 11/3 x + 10");
+        assert_eq!(histories[0].polynomial().to_string(), "3 x");
+        assert_eq!(histories[1].polynomial().to_string(), "1/2 x^2 + 3/2 x + 1");
+        assert_eq!(
+            parse_histories("0 0").unwrap()[0].polynomial().to_string(),
+            "0"
+        );
+        assert_eq!(
+            parse_histories("-1 -2 -5 -10").unwrap()[0]
+                .polynomial()
+                .to_string(),
+            "-x^2 - 1"
+        );
+
+        // the polynomial agrees with the differences everywhere
+        for x in -5..10 {
+            assert_eq!(
+                p.at(&BigRational::from_integer(x.into())),
+                BigRational::from_integer(histories[2].at(&x.into()))
+            );
+        }
+        let half = BigRational::new(1.into(), 2.into());
+        assert_eq!(p.at(&half), BigRational::new(93.into(), 8.into()));
+    }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT);
+        assert!(result.starts_with("line 1: degree 1, 3 x, next 18, previous -3\n"));
+        assert!(
+            result.contains("line 3: degree 3, 1/3 x^3 - x^2 + 11/3 x + 10, next 68, previous 5\n")
+        );
+        assert!(result
+            .ends_with("degree 1: 1 histories\ndegree 2: 1 histories\ndegree 3: 1 histories\n"));
+        assert_eq!(
+            explain("1 2 4"),
+            "line 1: the differences never reach zero\n"
+        );
+    }
 }
@@ -0,0 +1,141 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const PIPES: &[u8] = b"|-LJF7.";
+
+/// picks the pipe connecting the two given neighbors of a loop position
+fn pipe_between(prev: (isize, isize), pos: (isize, isize), next: (isize, isize)) -> u8 {
+    let dirs = [prev, next].map(|p| (p.0 - pos.0, p.1 - pos.1));
+    let has = |d: (isize, isize)| dirs.contains(&d);
+
+    match (has((0, -1)), has((1, 0)), has((0, 1)), has((-1, 0))) {
+        (true, _, true, _) => b'|',
+        (_, true, _, true) => b'-',
+        (true, true, _, _) => b'L',
+        (true, _, _, true) => b'J',
+        (_, true, true, _) => b'F',
+        _ => b'7',
+    }
+}
+
+/// generates a `size`x`size` pipe map (minimum 5) with one big loop and
+/// random pipes around it.
+/// the loop is the outline of a shape where every column is a single
+/// vertical run of cells, and neighboring runs always overlap.
+/// this guarantees a simple loop without any touching corners.
+/// the outermost rows and columns stay free of the loop, so the outside
+/// area is always connected
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(5) as isize;
+
+    // the shape uses cells between the loop corners, so the loop positions
+    // stay within 1..=size-2
+    let first = rng.gen_range(1..size / 2);
+    let last = rng.gen_range(size / 2 + 1..size - 1);
+
+    let mut columns: Vec<(isize, isize)> = Vec::new();
+    for _ in first..last {
+        let run = loop {
+            let (top, bottom) = match columns.last() {
+                Some(&(t, b)) => (t + rng.gen_range(-2..=2), b + rng.gen_range(-2..=2)),
+                None => {
+                    let t = rng.gen_range(1..size - 2);
+                    (t, rng.gen_range(t + 1..size - 1))
+                }
+            };
+            let (top, bottom) = (top.max(1), bottom.min(size - 2));
+
+            let overlaps = match columns.last() {
+                Some(&(t, b)) => top.max(t) < bottom.min(b),
+                None => true,
+            };
+            if top < bottom && overlaps {
+                break (top, bottom);
+            }
+        };
+        columns.push(run);
+    }
+
+    // walk the outline clockwise, one loop position at a time
+    let mut outline = Vec::new();
+    for (i, &(top, _)) in columns.iter().enumerate() {
+        let x = first + i as isize;
+        outline.push((x, top));
+        let next_top = columns.get(i + 1).map_or(columns[i].1, |c| c.0);
+        let step = if next_top > top { 1 } else { -1 };
+        let mut y = top;
+        while y != next_top {
+            outline.push((x + 1, y));
+            y += step;
+        }
+    }
+    for (i, &(_, bottom)) in columns.iter().enumerate().rev() {
+        let x = first + i as isize + 1;
+        outline.push((x, bottom));
+        let next_bottom = if i > 0 {
+            columns[i - 1].1
+        } else {
+            columns[0].0
+        };
+        let step = if next_bottom > bottom { 1 } else { -1 };
+        let mut y = bottom;
+        while y != next_bottom {
+            outline.push((x - 1, y));
+            y += step;
+        }
+    }
+
+    let mut grid = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| PIPES[rng.gen_range(0..PIPES.len())])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let len = outline.len();
+    for (i, &pos) in outline.iter().enumerate() {
+        let pipe = pipe_between(outline[(i + len - 1) % len], pos, outline[(i + 1) % len]);
+        grid[pos.1 as usize][pos.0 as usize] = pipe;
+    }
+
+    // place the start and clear its surroundings,
+    // so no other pipe combination can close a loop
+    let start = outline[rng.gen_range(0..len)];
+    for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+        let n = (start.0 + dx, start.1 + dy);
+        if n.0 >= 0 && n.1 >= 0 && n.0 < size && n.1 < size && !outline.contains(&n) {
+            grid[n.1 as usize][n.0 as usize] = b'.';
+        }
+    }
+    grid[start.1 as usize][start.0 as usize] = b'S';
+
+    grid.into_iter()
+        .map(|row| String::from_utf8(row).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    #[test]
+    fn closed_loop() {
+        for seed in 0..20 {
+            let input = generate(seed, 30);
+            assert_eq!(input.lines().count(), 30);
+
+            let pipe_map = crate::parse_pipes(&input);
+            // the walk around the loop ends where it started
+            assert_eq!(pipe_map.loop_pipes.last(), Some(pipe_map.get_start_pos()));
+            assert_eq!(pipe_map.get_length() % 2, 0);
+
+            let enclosed = [Method::Flood, Method::Shoelace, Method::Scanline]
+                .map(|method| pipe_map.enclosed(method));
+            assert!(enclosed[0] > 0);
+            assert!(enclosed.iter().all(|&e| e == enclosed[0]), "{}", seed);
+        }
+    }
+}
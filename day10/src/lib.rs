@@ -1,4 +1,38 @@
-use std::collections::HashMap;
+pub mod generator;
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+use tracing::{debug, instrument};
+
+/// how the tiles enclosed by the loop are counted
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Method {
+    /// flood fill a tile map with 3x3 tiles per pipe
+    #[default]
+    Flood,
+    /// shoelace formula for the area of the loop and Pick's theorem
+    Shoelace,
+    /// count tiles after an odd amount of loop crossings in every row
+    Scanline,
+}
+
+impl FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flood" => Ok(Method::Flood),
+            "shoelace" => Ok(Method::Shoelace),
+            "scanline" => Ok(Method::Scanline),
+            _ => Err(format!(
+                "unknown method \"{}\", use flood, shoelace or scanline",
+                s
+            )),
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq)]
 enum Dir {
@@ -54,6 +88,7 @@ struct TileMap {
 /// those would represent the inner tiles from the original pipe
 /// tiles.
 impl TileMap {
+    #[instrument(skip_all)]
     fn fill(&mut self, start: (isize, isize)) {
         let mut upcomming = vec![start];
 
@@ -174,6 +209,7 @@ impl PipeMap {
     /// to see which is closing the loop.
     /// it also walks the loop and collects all coordinates part of
     /// the loop
+    #[instrument(skip_all)]
     fn close_loop(&mut self) {
         let possible_pipes = [Pipe::NS, Pipe::WE, Pipe::NE, Pipe::SE, Pipe::SW, Pipe::NW];
 
@@ -189,10 +225,7 @@ impl PipeMap {
             };
 
             loop {
-                let next = self.walk(&pos, next_dir);
-                let Some((np, nd)) = next else {
-                    return None;
-                };
+                let (np, nd) = self.walk(&pos, next_dir)?;
                 pos = np;
                 next_dir = nd;
                 loop_pipes.push(pos);
@@ -206,6 +239,7 @@ impl PipeMap {
         });
 
         let (pipe, loop_pipes) = lengths.flatten().next().expect("One path should exist");
+        debug!(?pipe, length = loop_pipes.len(), "loop closed");
 
         self.loop_pipes = loop_pipes;
         self.set_start_pipe(pipe);
@@ -214,6 +248,7 @@ impl PipeMap {
     // this converts the pipe map into a tile map
     // every pipe tile is represented by a 3x3 group of tiles.
     // This is so the "squeeze beetwen pipes" can be simulated
+    #[instrument(skip_all)]
     fn create_tile_map(&self) -> TileMap {
         let mut tiles = HashMap::new();
         for (pos, p) in self.pipes.iter() {
@@ -302,8 +337,64 @@ impl PipeMap {
 
         TileMap { tiles }
     }
+
+    /// the loop encloses a polygon with the centers of the loop pipes as vertices.
+    /// Pick's theorem A = i + b/2 - 1 gives the enclosed tiles i
+    /// from its area A and the b loop pipes on its boundary
+    #[instrument(skip_all)]
+    fn shoelace_count(&self) -> isize {
+        let double_area = self
+            .loop_pipes
+            .iter()
+            .zip(self.loop_pipes.iter().cycle().skip(1))
+            .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            .sum::<isize>()
+            .abs();
+
+        (double_area - self.get_length() as isize) / 2 + 1
+    }
+
+    /// walks every row from the left, a tile is inside if an odd amount of
+    /// loop pipes with a northern end lie before it.
+    /// This way a bend like "F-7" is no crossing and "L-7" is a single one
+    #[instrument(skip_all)]
+    fn scanline_count(&self) -> isize {
+        let on_loop = self.loop_pipes.iter().collect::<HashSet<_>>();
+        let mut count = 0;
+
+        for y in 0..self.height as isize {
+            let mut inside = false;
+            for x in 0..self.width as isize {
+                if on_loop.contains(&(x, y)) {
+                    if matches!(
+                        self.pipes.get(&(x, y)),
+                        Some(Pipe::NS | Pipe::NE | Pipe::NW)
+                    ) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    fn enclosed(&self, method: Method) -> isize {
+        match method {
+            Method::Flood => {
+                let mut tile_map = self.create_tile_map();
+                tile_map.fill((0, 0));
+                tile_map.get_non_filled_center_count()
+            }
+            Method::Shoelace => self.shoelace_count(),
+            Method::Scanline => self.scanline_count(),
+        }
+    }
 }
 
+#[instrument(skip_all)]
 fn parse_pipes(input: &str) -> PipeMap {
     let mut pipes = HashMap::new();
 
@@ -330,20 +421,24 @@ fn parse_pipes(input: &str) -> PipeMap {
     PipeMap::new(pipes, width, height, start)
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     let pipe_map = parse_pipes(input);
 
     (pipe_map.get_length() / 2).to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
-    let pipe_map = parse_pipes(input);
-
-    let mut tile_map = pipe_map.create_tile_map();
+    part2_with_method(input, Method::Flood)
+}
 
-    tile_map.fill((0, 0));
+/// like `part2`, but counts the enclosed tiles with the given method
+#[instrument(skip_all)]
+pub fn part2_with_method(input: &str, method: Method) -> String {
+    let pipe_map = parse_pipes(input);
 
-    tile_map.get_non_filled_center_count().to_string()
+    pipe_map.enclosed(method).to_string()
 }
 
 #[cfg(test)]
@@ -428,4 +523,21 @@ L7JLJL-JLJLJL--JLJ.L";
         let result = part2(INPUT5);
         assert_eq!(result, "10");
     }
+
+    #[test]
+    fn methods() {
+        for (input, expected) in [(INPUT3, "4"), (INPUT4, "8"), (INPUT5, "10")] {
+            for method in [Method::Flood, Method::Shoelace, Method::Scanline] {
+                assert_eq!(part2_with_method(input, method), expected, "{:?}", method);
+            }
+        }
+
+        // the loop of the part 1 examples encloses a single tile
+        for method in [Method::Shoelace, Method::Scanline] {
+            assert_eq!(part2_with_method(INPUT1, method), "1");
+        }
+
+        assert_eq!("scanline".parse(), Ok(Method::Scanline));
+        assert!("pick".parse::<Method>().is_err());
+    }
 }
@@ -0,0 +1,41 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// generates a `size`x`size` image where roughly every 20th position is a galaxy
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+
+    let mut image = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.gen_ratio(1, 20) { b'#' } else { b'.' })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // there has to be at least one galaxy
+    image[rng.gen_range(0..size)][rng.gen_range(0..size)] = b'#';
+
+    image
+        .into_iter()
+        .map(|row| String::from_utf8(row).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn galaxies() {
+        let input = generate(1, 50);
+        assert_eq!(input.lines().count(), 50);
+        assert!(!crate::parse_galaxies(&input).is_empty());
+
+        // the distances grow linearly with the expansion
+        let distance = |expansion| crate::part2(&input, expansion).parse::<usize>().unwrap();
+        assert_eq!(distance(3) - distance(2), distance(2) - distance(1));
+        assert_eq!(crate::part1(&input), distance(2).to_string());
+    }
+}
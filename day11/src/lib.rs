@@ -1,5 +1,9 @@
+pub mod generator;
+
 use itertools::Itertools;
+use tracing::instrument;
 
+#[instrument(skip_all)]
 fn parse_galaxies(input: &str) -> Vec<(usize, usize)> {
     input
         .lines()
@@ -12,6 +16,7 @@ fn parse_galaxies(input: &str) -> Vec<(usize, usize)> {
         .collect::<Vec<_>>()
 }
 
+#[instrument(skip_all)]
 fn expand(galaxies: &mut [(usize, usize)], expansion: usize) {
     let max_x = galaxies
         .iter()
@@ -55,6 +60,7 @@ fn expand(galaxies: &mut [(usize, usize)], expansion: usize) {
     }
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     let mut galaxies = parse_galaxies(input);
     expand(&mut galaxies, 2);
@@ -67,6 +73,7 @@ pub fn part1(input: &str) -> String {
     distances.sum::<usize>().to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str, expansion: usize) -> String {
     let mut galaxies = parse_galaxies(input);
     expand(&mut galaxies, expansion);
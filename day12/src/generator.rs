@@ -0,0 +1,52 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// generates `size` rows of springs.
+/// every row is created from a fully known row where some springs are
+/// replaced with '?' afterwards, so there is always at least one arrangement
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let rows = (0..size)
+        .map(|_| {
+            let length = rng.gen_range(5..=20);
+            let mut springs = (0..length)
+                .map(|_| if rng.gen_bool(0.4) { '#' } else { '.' })
+                .collect::<Vec<_>>();
+
+            // there has to be at least one group of broken springs
+            let broken = rng.gen_range(0..length);
+            springs[broken] = '#';
+
+            let groups = springs
+                .split(|&s| s == '.')
+                .filter(|g| !g.is_empty())
+                .map(|g| g.len().to_string())
+                .collect::<Vec<_>>();
+
+            let springs = springs
+                .into_iter()
+                .map(|s| if rng.gen_bool(0.4) { '?' } else { s })
+                .collect::<String>();
+
+            format!("{} {}", springs, groups.join(","))
+        })
+        .collect::<Vec<_>>();
+
+    rows.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrangements() {
+        let input = generate(1, 100);
+        let rows = crate::parse_springs(&input);
+        assert_eq!(rows.len(), 100);
+        for row in rows.iter() {
+            assert!(!row.groups.is_empty());
+            assert!(row.get_arrangements() > 0);
+        }
+    }
+}
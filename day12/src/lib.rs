@@ -1,6 +1,9 @@
 /// needed some inspiration from reddit for this one
 /// https://www.reddit.com/r/adventofcode/comments/18ge41g/2023_day_12_solutions/
 use std::{collections::HashMap, iter::once};
+use tracing::instrument;
+
+pub mod generator;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum State {
@@ -143,6 +146,7 @@ impl SpringRow {
     }
 }
 
+#[instrument(skip_all)]
 fn parse_springs(input: &str) -> Vec<SpringRow> {
     input
         .lines()
@@ -159,6 +163,7 @@ fn parse_springs(input: &str) -> Vec<SpringRow> {
         .collect::<Vec<_>>()
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     let spring_rows = parse_springs(input);
 
@@ -169,6 +174,7 @@ pub fn part1(input: &str) -> String {
         .to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
     let mut spring_rows = parse_springs(input);
 
@@ -0,0 +1,111 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    if parents[i] != i {
+        parents[i] = find(parents, parents[i]);
+    }
+    parents[i]
+}
+
+/// position of `pos` when mirrored between `line - 1` and `line`, if it is inside `0..len`
+fn mirrored(pos: usize, line: usize, len: usize) -> Option<usize> {
+    (2 * line).checked_sub(pos + 1).filter(|&m| m < len)
+}
+
+/// creates a pattern with a perfect mirror between row `row - 1` and `row`
+/// and a mirror between column `col - 1` and `col` that is broken by a single smudge
+fn pattern(rng: &mut StdRng, width: usize, height: usize, row: usize, col: usize) -> Vec<Vec<u8>> {
+    // group all positions that have to be equal for both mirrors
+    let mut parents = (0..width * height).collect::<Vec<_>>();
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            if let Some(my) = mirrored(y, row, height) {
+                let a = find(&mut parents, i);
+                parents[a] = find(&mut parents, my * width + x);
+            }
+            if let Some(mx) = mirrored(x, col, width) {
+                let a = find(&mut parents, i);
+                parents[a] = find(&mut parents, y * width + mx);
+            }
+        }
+    }
+
+    let colors = (0..width * height)
+        .map(|_| if rng.gen_bool(0.5) { b'#' } else { b'.' })
+        .collect::<Vec<_>>();
+    let mut grid = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| colors[find(&mut parents, y * width + x)])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // the smudge has to be outside of the rows covered by the row mirror,
+    // but inside of the columns covered by the column mirror
+    let rows = (0..height)
+        .filter(|&y| mirrored(y, row, height).is_none())
+        .collect::<Vec<_>>();
+    let cols = (0..width)
+        .filter(|&x| mirrored(x, col, width).is_some())
+        .collect::<Vec<_>>();
+
+    let y = rows[rng.gen_range(0..rows.len())];
+    let x = cols[rng.gen_range(0..cols.len())];
+    grid[y][x] = if grid[y][x] == b'#' { b'.' } else { b'#' };
+
+    grid
+}
+
+/// generates `size` patterns of 5 to 17 rows and columns.
+/// every pattern has one perfect mirror and another mirror that needs one
+/// smudge to be fixed. the two mirrors are randomly horizontal or vertical
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let patterns = (0..size)
+        .map(|_| {
+            let width = rng.gen_range(5..=17);
+            let height = rng.gen_range(5..=17);
+            let col = rng.gen_range(1..width);
+            // the perfect mirror must not cover all rows
+            let row = loop {
+                let row = rng.gen_range(1..height);
+                if 2 * row != height {
+                    break row;
+                }
+            };
+
+            let mut grid = pattern(&mut rng, width, height, row, col);
+
+            if rng.gen_bool(0.5) {
+                grid = (0..width)
+                    .map(|x| grid.iter().map(|line| line[x]).collect())
+                    .collect();
+            }
+
+            grid.into_iter()
+                .map(|line| String::from_utf8(line).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>();
+
+    patterns.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrors() {
+        let patterns = crate::parse_pattern(&generate(1, 100));
+        assert_eq!(patterns.len(), 100);
+        // fixing the smudge always leads to another mirror
+        for pattern in patterns.iter() {
+            assert_ne!(pattern.find_mirror(), pattern.find_smudge_mirror());
+        }
+    }
+}
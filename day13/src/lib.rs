@@ -1,9 +1,22 @@
+pub mod generator;
+
+use tracing::instrument;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Mirror {
     Row(usize),
     Column(usize),
 }
 
+impl Mirror {
+    fn describe(&self) -> String {
+        match self {
+            Mirror::Row(x) => format!("horizontal between rows {} and {}", x, x + 1),
+            Mirror::Column(x) => format!("vertical between columns {} and {}", x, x + 1),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Pattern {
     original: Vec<String>,
@@ -94,6 +107,7 @@ impl Pattern {
     }
 }
 
+#[instrument(skip_all)]
 fn parse_pattern(input: &str) -> Vec<Pattern> {
     input
         .split("\n\n")
@@ -104,6 +118,7 @@ fn parse_pattern(input: &str) -> Vec<Pattern> {
         .collect::<Vec<_>>()
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     let pattern = parse_pattern(input);
 
@@ -115,6 +130,7 @@ pub fn part1(input: &str) -> String {
     mirror_values.sum::<usize>().to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
     let pattern = parse_pattern(input);
 
@@ -126,6 +142,24 @@ pub fn part2(input: &str) -> String {
     mirror_values.sum::<usize>().to_string()
 }
 
+/// lists the mirror of every pattern, without and with fixing the smudge
+pub fn explain(input: &str) -> String {
+    let pattern = parse_pattern(input);
+
+    pattern
+        .iter()
+        .enumerate()
+        .map(|(index, p)| {
+            format!(
+                "pattern {}: {}, with smudge {}\n",
+                index + 1,
+                p.find_mirror().describe(),
+                p.find_smudge_mirror().describe()
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +192,16 @@ mod tests {
         let result = part2(INPUT);
         assert_eq!(result, "400");
     }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT);
+        assert_eq!(
+            result,
+            "\
+pattern 1: vertical between columns 5 and 6, with smudge horizontal between rows 3 and 4
+pattern 2: horizontal between rows 4 and 5, with smudge horizontal between rows 1 and 2
+"
+        );
+    }
 }
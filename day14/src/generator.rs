@@ -0,0 +1,45 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// generates a `size`x`size` platform with rounded rocks and cube rocks
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let lines = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| match rng.gen_range(0..100) {
+                    0..=19 => 'O',
+                    20..=34 => '#',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tile;
+
+    #[test]
+    fn platform() {
+        let input = generate(1, 30);
+        let mut grid = crate::parse_grid(&input);
+        assert_eq!((grid.get_width(), grid.get_height()), (30, 30));
+
+        let rocks = |grid: &crate::Grid| grid.iter().filter(|(_, &t)| t == Tile::Rock).count();
+        let before = rocks(&grid);
+        assert!(before > 0);
+
+        // tilting moves the rocks, but never removes them
+        grid.tilt(crate::Dir::N);
+        assert_eq!(rocks(&grid), before);
+        assert!(grid
+            .iter()
+            .filter(|&((_, y), &t)| t == Tile::Rock && y > 0)
+            .all(|((x, y), _)| grid.get((x, y - 1)) != Some(Tile::Empty)));
+    }
+}
@@ -1,6 +1,10 @@
+pub mod generator;
+
+use budget::{Budget, Exhausted};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::{fmt, ops::Range};
+use tracing::{debug, instrument, trace};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Tile {
@@ -177,6 +181,7 @@ impl fmt::Display for Grid {
     }
 }
 
+#[instrument(skip_all)]
 fn parse_grid(input: &str) -> Grid {
     let mut width = 0;
     let tiles = input
@@ -192,15 +197,16 @@ fn parse_grid(input: &str) -> Grid {
     Grid::new(tiles, width)
 }
 
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     let mut grid = parse_grid(input);
 
     let height = grid.get_height();
-    // println!("{}", &grid);
+    trace!("before tilting\n{}", &grid);
 
     grid.tilt(Dir::N);
 
-    // println!("{}", &grid);
+    trace!("after tilting\n{}", &grid);
 
     let loads = grid
         .iter()
@@ -210,6 +216,13 @@ pub fn part1(input: &str) -> String {
 }
 
 pub fn part2(input: &str) -> String {
+    part2_with_budget(input, &Budget::unlimited()).expect("budget is unlimited")
+}
+
+/// part 2 with a budget check for every spin cycle,
+/// in case no repetition is found and all cycles have to be simulated
+#[instrument(skip_all)]
+pub fn part2_with_budget(input: &str, budget: &Budget) -> Result<String, Exhausted> {
     let mut grid = parse_grid(input);
 
     let height = grid.get_height();
@@ -220,6 +233,8 @@ pub fn part2(input: &str) -> String {
     let mut cycle_found = false;
 
     while step < 1000000000 {
+        budget.step()?;
+
         grid.tilt(Dir::N);
         grid.tilt(Dir::W);
         grid.tilt(Dir::S);
@@ -235,6 +250,7 @@ pub fn part2(input: &str) -> String {
             {
                 cycle_found = true;
                 let diff = step - (index - 1);
+                debug!(step, start = index - 1, length = diff, "cycle found");
                 while step < 1000000000 {
                     step += diff;
                 }
@@ -251,7 +267,7 @@ pub fn part2(input: &str) -> String {
         .iter()
         .filter_map(|(p, t)| (*t == Tile::Rock).then_some(height - p.1));
 
-    (loads.sum::<usize>()).to_string()
+    Ok((loads.sum::<usize>()).to_string())
 }
 
 #[cfg(test)]
@@ -281,4 +297,10 @@ O.#..O.#.#
         let result = part2(INPUT);
         assert_eq!(result, "64");
     }
+
+    #[test]
+    fn p2_budget() {
+        let result = part2_with_budget(INPUT, &Budget::unlimited().with_steps(5));
+        assert_eq!(result, Err(Exhausted::Steps));
+    }
 }
@@ -0,0 +1,49 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// generates an initialization sequence of `size` steps.
+/// labels are drawn from a pool of `size / 4 + 1` labels,
+/// so lenses get replaced and removed regularly
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let labels = (0..size / 4 + 1)
+        .map(|_| {
+            (0..rng.gen_range(2..=6))
+                .map(|_| rng.gen_range(b'a'..=b'z') as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+
+    let steps = (0..size)
+        .map(|_| {
+            let label = &labels[rng.gen_range(0..labels.len())];
+            if rng.gen_bool(0.7) {
+                format!("{}={}", label, rng.gen_range(1..=9))
+            } else {
+                format!("{}-", label)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    steps.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn steps() {
+        let input = generate(1, 1000);
+        let steps = crate::parse_steps(&input);
+        assert_eq!(steps.len(), 1000);
+
+        let labels = steps.iter().map(|(l, _)| *l).collect::<HashSet<_>>();
+        assert!(labels.len() <= 1000 / 4 + 1);
+        assert!(labels.iter().all(|l| (2..=6).contains(&l.len())));
+        // lenses are added as well as removed
+        assert!(steps.iter().any(|&(_, focal)| focal == 0));
+        assert!(steps.iter().all(|&(_, focal)| focal <= 9));
+    }
+}
@@ -1,4 +1,7 @@
+pub mod generator;
+
 use core::array::from_fn;
+use tracing::instrument;
 
 // label + focal length
 type Lens<'a> = (&'a str, u8);
@@ -13,7 +16,8 @@ fn hash(input: &str) -> u32 {
         .fold(0, |acc, curr| ((acc + *curr as u32) * 17) % 256)
 }
 
-fn parse_steps(input: &str) -> Vec<Step> {
+#[instrument(skip_all)]
+fn parse_steps(input: &str) -> Vec<Step<'_>> {
     input
         .split(',')
         .map(|step| {
@@ -26,34 +30,41 @@ fn parse_steps(input: &str) -> Vec<Step> {
         .collect()
 }
 
+/// applies a single step of the initialization sequence to the boxes
+fn apply<'a>(boxes: &mut [Vec<Lens<'a>>; 256], (label, focal): Step<'a>) {
+    let b = hash(label) as usize;
+    let b = boxes
+        .get_mut(b)
+        .expect("calculated hash should be between 0-255");
+    if focal == 0 {
+        // remove lens
+        b.retain(|&(l, _)| l != label);
+    } else {
+        // add or replace lens
+        if let Some(index) = b
+            .iter()
+            .enumerate()
+            .find_map(|(index, l)| (l.0 == label).then_some(index))
+        {
+            b[index] = (label, focal);
+        } else {
+            b.push((label, focal));
+        }
+    }
+}
+
+#[instrument(skip_all)]
 pub fn part1(input: &str) -> String {
     input.split(',').map(hash).sum::<u32>().to_string()
 }
 
+#[instrument(skip_all)]
 pub fn part2(input: &str) -> String {
     let mut boxes = from_fn::<Vec<Lens>, 256, _>(|_| Vec::new());
     let steps = parse_steps(input);
 
-    for (label, focal) in steps {
-        let b = hash(label) as usize;
-        let b = boxes
-            .get_mut(b)
-            .expect("calculated hash should be between 0-255");
-        if focal == 0 {
-            // remove lens
-            b.retain(|&(l, _)| l != label);
-        } else {
-            // add or replace lens
-            if let Some(index) = b
-                .iter()
-                .enumerate()
-                .find_map(|(index, l)| (l.0 == label).then_some(index))
-            {
-                b[index] = (label, focal);
-            } else {
-                b.push((label, focal));
-            }
-        }
+    for step in steps {
+        apply(&mut boxes, step);
     }
 
     // calculate the focusing power
@@ -71,6 +82,28 @@ pub fn part2(input: &str) -> String {
     power.to_string()
 }
 
+/// lists the content of all non empty boxes after every step
+pub fn explain(input: &str) -> String {
+    let mut boxes = from_fn::<Vec<Lens>, 256, _>(|_| Vec::new());
+    let mut explanation = String::new();
+
+    for (raw, step) in input.split(',').zip(parse_steps(input)) {
+        apply(&mut boxes, step);
+
+        explanation += &format!("After \"{}\" (hash {}):\n", raw, hash(raw));
+        for (index, b) in boxes.iter().enumerate().filter(|(_, b)| !b.is_empty()) {
+            let lenses = b
+                .iter()
+                .map(|(label, focal)| format!("[{} {}]", label, focal))
+                .collect::<Vec<_>>();
+            explanation += &format!("Box {}: {}\n", index, lenses.join(" "));
+        }
+        explanation += "\n";
+    }
+
+    explanation
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,4 +122,13 @@ rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
         let result = part2(INPUT);
         assert_eq!(result, "145");
     }
+
+    #[test]
+    fn explanation() {
+        let result = explain(INPUT);
+        assert!(result.starts_with("After \"rn=1\" (hash 30):\nBox 0: [rn 1]\n\n"));
+        assert!(result.ends_with(
+            "After \"ot=7\" (hash 231):\nBox 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]\n\n"
+        ));
+    }
 }